@@ -0,0 +1,6 @@
+use chess_ai_app::{run_app, window_conf};
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    run_app().await;
+}
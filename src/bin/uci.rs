@@ -0,0 +1,380 @@
+//! Universal Chess Interface front end for the engine, for use with
+//! cutechess-cli, Arena and other chess GUIs.
+
+use std::io::{self, BufRead};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use chess::{Board, ChessMove, Color as ChessColor};
+use chess_ai_app::engine::bench::{run_bench, run_tactics, DEFAULT_BENCH_DEPTH, DEFAULT_TACTICS_DEPTH};
+use chess_ai_app::engine::book::OpeningBook;
+use chess_ai_app::engine::difficulty::{pick_move, Difficulty};
+use chess_ai_app::engine::fen::{fullmove_number, halfmove_clock, parse_fen};
+use chess_ai_app::engine::perft::{divide, perft};
+use chess_ai_app::engine::search::{mate_in, time_budget_ms, SearchFeatures, SearchLimits, SearchResult};
+use chess_ai_app::engine::syzygy::Tablebase;
+use chess_ai_app::engine::tt::DEFAULT_SIZE_MB;
+use chess_ai_app::engine::{Engine, GameRecord};
+use rand::thread_rng;
+
+const MAX_HASH_MB: usize = 1024;
+const MAX_MULTI_PV: usize = 8;
+const MAX_SKILL: u8 = 20;
+/// Check options that switch search techniques off one at a time, to
+/// measure each with `bench` and `tactics`.
+const FEATURE_OPTIONS: [&str; 5] = ["PVS", "AspirationWindows", "NullMove", "LMR", "CheckExtensions"];
+
+fn main() {
+    let mut uci = Uci::new();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if !uci.handle(&line) {
+            break;
+        }
+    }
+    uci.stop();
+}
+
+struct Uci {
+    /// The position to search and the moves that led to it.
+    game: GameRecord,
+    engine: Arc<Mutex<Engine>>,
+    skill: u8,
+    multi_pv: usize,
+    features: SearchFeatures,
+    /// Play from `book` while it has moves, if one was loaded.
+    own_book: bool,
+    book: Option<OpeningBook>,
+    search: Option<(Arc<AtomicBool>, JoinHandle<()>)>,
+}
+
+impl Uci {
+    fn new() -> Self {
+        Uci {
+            game: GameRecord::default(),
+            engine: Arc::new(Mutex::new(Engine::new())),
+            skill: MAX_SKILL,
+            multi_pv: 1,
+            features: SearchFeatures::default(),
+            own_book: true,
+            book: None,
+            search: None,
+        }
+    }
+
+    /// Handles one command line. Returns `false` on `quit`.
+    fn handle(&mut self, line: &str) -> bool {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("uci") => {
+                println!("id name Chess AI");
+                println!("id author TWi5td");
+                println!("option name Hash type spin default {DEFAULT_SIZE_MB} min 1 max {MAX_HASH_MB}");
+                println!("option name Skill Level type spin default {MAX_SKILL} min 0 max {MAX_SKILL}");
+                println!("option name MultiPV type spin default 1 min 1 max {MAX_MULTI_PV}");
+                println!("option name OwnBook type check default true");
+                println!("option name Book File type string default <empty>");
+                println!("option name SyzygyPath type string default <empty>");
+                for name in FEATURE_OPTIONS {
+                    println!("option name {name} type check default true");
+                }
+                println!("uciok");
+            }
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => {
+                self.stop();
+                self.engine.lock().unwrap().new_game();
+                self.game = GameRecord::default();
+            }
+            Some("setoption") => {
+                self.stop();
+                self.set_option(&tokens.collect::<Vec<_>>());
+            }
+            Some("position") => {
+                self.stop();
+                match parse_position(&tokens.collect::<Vec<_>>()) {
+                    Ok(game) => self.game = game,
+                    Err(err) => println!("info string {err}"),
+                }
+            }
+            Some("go") => {
+                self.stop();
+                let args: Vec<&str> = tokens.collect();
+                match args.as_slice() {
+                    ["perft", depth, ..] => run_perft(&self.game.board(), depth, true),
+                    _ => self.go(&args),
+                }
+            }
+            // Move generation checks: node count, optionally split by root move
+            Some("perft") => run_perft(&self.game.board(), tokens.next().unwrap_or("1"), false),
+            Some("divide") => run_perft(&self.game.board(), tokens.next().unwrap_or("1"), true),
+            // Search speed and tree size over a fixed position set
+            Some("bench") => match tokens.next().map_or(Ok(DEFAULT_BENCH_DEPTH), str::parse) {
+                Ok(depth) => {
+                    let bench = run_bench(depth, self.features);
+                    println!("Nodes searched: {}", bench.nodes);
+                    println!("Time: {} ms ({} nps)", bench.elapsed_ms, bench.nodes * 1000 / bench.elapsed_ms.max(1));
+                }
+                Err(_) => println!("info string invalid bench depth"),
+            },
+            // How many of a fixed set of tactics the search solves
+            Some("tactics") => match tokens.next().map_or(Ok(DEFAULT_TACTICS_DEPTH), str::parse) {
+                Ok(depth) => {
+                    let tactics = run_tactics(depth, self.features);
+                    println!("Solved: {}/{}", tactics.solved, tactics.total);
+                    println!("Nodes searched: {}", tactics.nodes);
+                    println!("Time: {} ms", tactics.elapsed_ms);
+                }
+                Err(_) => println!("info string invalid tactics depth"),
+            },
+            Some("stop") => self.stop(),
+            Some("quit") => return false,
+            Some(other) => println!("info string unknown command {other}"),
+            None => {}
+        }
+        true
+    }
+
+    fn set_option(&mut self, args: &[&str]) {
+        // setoption name <id with spaces> value <x>
+        let value_at = args.iter().position(|&t| t == "value");
+        let ["name", rest @ ..] = args else {
+            println!("info string setoption needs a name");
+            return;
+        };
+        let name = rest[..value_at.map_or(rest.len(), |i| i - 1)].join(" ");
+        // Joined back up, since file names may contain spaces
+        let value = value_at.map_or(String::new(), |i| args[i + 1..].join(" "));
+
+        match name.to_ascii_lowercase().as_str() {
+            "hash" => match value.parse::<usize>() {
+                Ok(mb) => self.engine.lock().unwrap().set_hash_mb(mb.clamp(1, MAX_HASH_MB)),
+                Err(_) => println!("info string invalid Hash value {value}"),
+            },
+            "skill level" => match value.parse::<u8>() {
+                Ok(level) => self.skill = level.min(MAX_SKILL),
+                Err(_) => println!("info string invalid Skill Level value {value}"),
+            },
+            "ownbook" => self.own_book = value.eq_ignore_ascii_case("true"),
+            "book file" if value.is_empty() || value == "<empty>" => self.book = None,
+            "book file" => match read_book(&value) {
+                Ok(book) => {
+                    println!("info string loaded {} book entries from {value}", book.len());
+                    self.book = Some(book);
+                }
+                Err(err) => println!("info string could not load book {value}: {err}"),
+            },
+            "syzygypath" if value.is_empty() || value == "<empty>" => self.engine.lock().unwrap().set_tablebase(None),
+            // Without usable tables the search goes on as before
+            "syzygypath" => match Tablebase::open(&value) {
+                Ok(tb) => {
+                    println!("info string found {} Syzygy tables up to {} pieces in {value}", tb.len(), tb.max_pieces());
+                    self.engine.lock().unwrap().set_tablebase(Some(tb));
+                }
+                Err(err) => println!("info string {err}"),
+            },
+            "multipv" => match value.parse::<usize>() {
+                Ok(lines) => self.multi_pv = lines.clamp(1, MAX_MULTI_PV),
+                Err(_) => println!("info string invalid MultiPV value {value}"),
+            },
+            other => match feature_switch(&mut self.features, other) {
+                Some(switch) => *switch = value.eq_ignore_ascii_case("true"),
+                None => println!("info string unknown option {name}"),
+            },
+        }
+    }
+
+    fn go(&mut self, args: &[&str]) {
+        let mut limits = SearchLimits { multi_pv: self.multi_pv, features: self.features, ..Default::default() };
+        let mut infinite = false;
+        let (mut wtime, mut btime, mut winc, mut binc, mut movestogo) = (None, None, 0, 0, None);
+
+        let mut it = args.iter();
+        while let Some(&arg) = it.next() {
+            let mut number = || it.next().and_then(|v| v.parse::<u64>().ok());
+            match arg {
+                "depth" => limits.depth = number().map(|d| d as i32),
+                "nodes" => limits.nodes = number(),
+                "movetime" => limits.time_ms = number(),
+                "wtime" => wtime = number(),
+                "btime" => btime = number(),
+                "winc" => winc = number().unwrap_or(0),
+                "binc" => binc = number().unwrap_or(0),
+                "movestogo" => movestogo = number(),
+                "infinite" => infinite = true,
+                _ => {}
+            }
+        }
+
+        let (clock, inc) = match self.game.board().side_to_move() {
+            ChessColor::White => (wtime, winc),
+            ChessColor::Black => (btime, binc),
+        };
+        if let (None, Some(remaining)) = (limits.time_ms, clock) {
+            limits.time_ms = Some(time_budget_ms(remaining, inc, movestogo));
+        }
+        if infinite {
+            limits = SearchLimits { nodes: None, time_ms: None, ..limits };
+        }
+
+        let handicap = skill_difficulty(self.skill);
+        // Full strength plays the book like the hardest level
+        let book_profile = handicap.unwrap_or(Difficulty::Hard).profile();
+        let book = self.book.as_ref().filter(|_| self.own_book && !infinite);
+        if let Some(mv) = book.and_then(|book| book_profile.book_move(book, &self.game, &mut thread_rng())) {
+            println!("info string book move");
+            println!("bestmove {mv}");
+            return;
+        }
+
+        if let Some(difficulty) = handicap {
+            let profile = difficulty.profile().limits();
+            limits.depth = Some(limits.depth.map_or(profile.depth.unwrap(), |d| d.min(profile.depth.unwrap())));
+            limits.exact_root_scores = profile.exact_root_scores;
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = spawn_search(self.game.clone(), limits, infinite, handicap, self.engine.clone(), stop.clone());
+        self.search = Some((stop, handle));
+    }
+
+    /// Stops a running search and waits for its `bestmove`.
+    fn stop(&mut self) {
+        if let Some((stop, handle)) = self.search.take() {
+            stop.store(true, Ordering::Relaxed);
+            let _ = handle.join();
+        }
+    }
+}
+
+/// The flag in `features` behind one of `FEATURE_OPTIONS`, by its
+/// lowercased name.
+fn feature_switch<'a>(features: &'a mut SearchFeatures, name: &str) -> Option<&'a mut bool> {
+    match name {
+        "pvs" => Some(&mut features.pvs),
+        "aspirationwindows" => Some(&mut features.aspiration),
+        "nullmove" => Some(&mut features.null_move),
+        "lmr" => Some(&mut features.lmr),
+        "checkextensions" => Some(&mut features.check_extensions),
+        _ => None,
+    }
+}
+
+fn read_book(path: &str) -> Result<OpeningBook, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    OpeningBook::from_bytes(&bytes)
+}
+
+/// Skill Level 20 is full strength; lower levels play like the GUI's
+/// difficulty settings.
+fn skill_difficulty(level: u8) -> Option<Difficulty> {
+    match level {
+        0..=6 => Some(Difficulty::Easy),
+        7..=13 => Some(Difficulty::Medium),
+        14..=19 => Some(Difficulty::Hard),
+        _ => None,
+    }
+}
+
+/// The position and the moves played from it, so the search can see
+/// repetitions.
+fn parse_position(args: &[&str]) -> Result<GameRecord, String> {
+    let moves_at = args.iter().position(|&t| t == "moves").unwrap_or(args.len());
+    let mut game = match args.first() {
+        Some(&"startpos") => GameRecord::default(),
+        Some(&"fen") => {
+            let fen = args[1..moves_at].join(" ");
+            let board = parse_fen(&fen).map_err(|e| format!("invalid fen {fen}: {e}"))?;
+            GameRecord::new(board).with_halfmove_clock(halfmove_clock(&fen)).with_fullmove_number(fullmove_number(&fen))
+        }
+        _ => return Err("position needs startpos or fen".to_string()),
+    };
+
+    for text in args.iter().skip(moves_at + 1) {
+        let mv = ChessMove::from_str(text)
+            .ok()
+            .filter(|&mv| game.board().legal(mv))
+            .ok_or_else(|| format!("illegal move {text}"))?;
+        game.push(mv);
+    }
+    Ok(game)
+}
+
+fn spawn_search(
+    game: GameRecord,
+    limits: SearchLimits,
+    infinite: bool,
+    handicap: Option<Difficulty>,
+    engine: Arc<Mutex<Engine>>,
+    stop: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut engine = engine.lock().unwrap();
+        engine.set_game(&game);
+        let result = engine.search_with(limits, Some(&stop), print_info);
+
+        // In infinite mode the GUI expects bestmove only after it sends stop.
+        while infinite && !stop.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        let best = match handicap {
+            Some(difficulty) => pick_move(&result.root_scores, &difficulty.profile(), &mut thread_rng()),
+            None => result.best_move,
+        };
+        match best {
+            Some(mv) => println!("bestmove {mv}"),
+            None => println!("bestmove 0000"),
+        }
+    })
+}
+
+fn run_perft(board: &Board, depth: &str, split: bool) {
+    let Ok(depth) = depth.parse::<u32>() else {
+        println!("info string invalid perft depth {depth}");
+        return;
+    };
+
+    let start = Instant::now();
+    let nodes = if split {
+        let counts = divide(board, depth);
+        for (mv, count) in &counts {
+            println!("{mv}: {count}");
+        }
+        counts.iter().map(|&(_, count)| count).sum()
+    } else {
+        perft(board, depth)
+    };
+    let ms = start.elapsed().as_millis() as u64;
+    println!();
+    println!("Nodes searched: {nodes}");
+    println!("Time: {ms} ms ({} nps)", nodes * 1000 / ms.max(1));
+}
+
+/// One `info` line per reported line, numbered with `multipv` when the
+/// GUI asked for more than one.
+fn print_info(result: &SearchResult) {
+    let nps = result.nodes * 1000 / result.elapsed_ms.max(1);
+    for (index, line) in result.lines.iter().enumerate() {
+        let score = match mate_in(line.score) {
+            Some(moves) => format!("mate {moves}"),
+            None => format!("cp {}", line.score),
+        };
+        let multi_pv = if result.lines.len() > 1 { format!(" multipv {}", index + 1) } else { String::new() };
+        let pv: Vec<String> = line.pv.iter().map(|mv| mv.to_string()).collect();
+        println!(
+            "info depth {}{} score {} nodes {} nps {} time {} hashfull {} pv {}",
+            result.depth,
+            multi_pv,
+            score,
+            result.nodes,
+            nps,
+            result.elapsed_ms,
+            result.tt_stats.fill_permille,
+            pv.join(" ")
+        );
+    }
+}
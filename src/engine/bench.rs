@@ -0,0 +1,94 @@
+//! A fixed set of positions searched to a fixed depth. The node total is
+//! deterministic, so it shows at a glance whether a search change (move
+//! ordering, pruning) makes the tree smaller, and the time gives a rough
+//! speed figure. The tactics suite checks that a change which shrinks
+//! the tree still finds the winning move in sharp positions.
+
+use std::str::FromStr;
+
+use chess::{Board, ChessMove};
+use web_time::Instant;
+
+use crate::engine::search::{choose_best_move_ab, SearchFeatures, SearchLimits};
+use crate::engine::tt::TranspositionTable;
+
+pub const DEFAULT_BENCH_DEPTH: i32 = 5;
+pub const DEFAULT_TACTICS_DEPTH: i32 = 6;
+
+/// Openings, middlegames with tactics, and endgames.
+pub const BENCH_POSITIONS: [&str; 12] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+    "r1bq1rk1/ppp2ppp/2np1n2/2b1p3/2B1P3/2NP1N2/PPP2PPP/R1BQ1RK1 w - - 0 7",
+    "rnbqkb1r/pp1p1ppp/4pn2/2p5/2PP4/2N5/PP2PPPP/R1BQKBNR w KQkq - 0 4",
+    "r2q1rk1/pp2bppp/2n1pn2/3p4/3P1B2/2PBPN2/PP1N1PPP/R2QK2R w KQ - 0 10",
+    "2r3k1/pp3ppp/4pn2/q2p4/3P4/P1Q1PN2/5PPP/2R3K1 w - - 0 22",
+    "r1b2rk1/2q1bppp/p2ppn2/1p6/3BPP2/2N2B2/PPP3PP/R2Q1R1K w - - 0 14",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1",
+    "8/8/4k3/8/2p5/8/B2K4/8 w - - 0 1",
+    "8/5pk1/6p1/7p/3R3P/6P1/r4PK1/8 b - - 0 40",
+];
+
+/// Positions from Win at Chess with the move that wins, in UCI notation.
+pub const TACTICS: [(&str, &str); 12] = [
+    ("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1", "g3g6"),
+    ("5rk1/1ppb3p/p1pb4/6q1/3P1p1r/2P1R2P/PP1BQ1P1/5RKN w - - 0 1", "e3g3"),
+    ("r1bq2rk/pp3pbp/2p1p1pQ/7P/3P4/2PB1N2/PP3PPR/2KR4 w - - 0 1", "h6h7"),
+    ("5k2/6pp/p1qN4/1p1p4/3P4/2PKP2Q/PP3r2/3R4 b - - 0 1", "c6c4"),
+    ("7k/p7/1R5K/6r1/6p1/6P1/8/8 w - - 0 1", "b6b7"),
+    ("rnbqkb1r/pppp1ppp/8/4P3/6n1/7P/PPPNPPP1/R1BQKBNR b KQkq - 0 1", "g4e3"),
+    ("r4q1k/p2bR1rp/2p2Q1N/5p2/5p2/2P5/PP3PPP/R5K1 w - - 0 1", "e7f7"),
+    ("3q1rk1/p4pp1/2pb3p/3p4/6Pr/1PNQ4/P1PB1PP1/4RRK1 b - - 0 1", "d6h2"),
+    ("2br2k1/2q3rn/p2NppQ1/2p1P3/Pp5R/4P3/1P3PPP/3R2K1 w - - 0 1", "h4h7"),
+    ("r1b1kb1r/3q1ppp/pBp1pn2/8/Np3P2/5B2/PPP3PP/R2Q1RK1 w kq - 0 1", "f3c6"),
+    ("4k1r1/2p3r1/1pR1p3/3pP2p/3P2qP/P4N2/1PQ4P/5R1K b - - 0 1", "g4f3"),
+    ("r3r1k1/ppqb1ppp/8/4p1NQ/8/2P5/PP3PPP/R3R1K1 b - - 0 1", "d7f5"),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BenchResult {
+    pub nodes: u64,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TacticsResult {
+    pub solved: usize,
+    pub total: usize,
+    pub nodes: u64,
+    pub elapsed_ms: u64,
+}
+
+/// Searches every bench position to `depth` with the given search
+/// features, each with a fresh transposition table so the total does not
+/// depend on the order.
+pub fn run_bench(depth: i32, features: SearchFeatures) -> BenchResult {
+    let start = Instant::now();
+    let nodes = BENCH_POSITIONS.iter().map(|fen| search(fen, depth, features).1).sum();
+    BenchResult { nodes, elapsed_ms: start.elapsed().as_millis() as u64 }
+}
+
+/// Searches every tactics position to `depth` and counts how many times
+/// the winning move comes out on top.
+pub fn run_tactics(depth: i32, features: SearchFeatures) -> TacticsResult {
+    let start = Instant::now();
+    let mut result = TacticsResult { total: TACTICS.len(), ..Default::default() };
+    for (fen, best) in TACTICS {
+        let (found, nodes) = search(fen, depth, features);
+        result.nodes += nodes;
+        if found == ChessMove::from_str(best).ok() {
+            result.solved += 1;
+        }
+    }
+    result.elapsed_ms = start.elapsed().as_millis() as u64;
+    result
+}
+
+fn search(fen: &str, depth: i32, features: SearchFeatures) -> (Option<ChessMove>, u64) {
+    let board = Board::from_str(fen).expect("bench positions are valid");
+    let limits = SearchLimits { features, ..SearchLimits::depth(depth) };
+    let result = choose_best_move_ab(&board, limits, &mut TranspositionTable::new(16));
+    (result.best_move, result.nodes)
+}
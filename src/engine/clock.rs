@@ -0,0 +1,178 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chess::Color as ChessColor;
+use web_time::Duration;
+
+/// What a player gets back for each move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bonus {
+    /// Fischer increment, added after every move.
+    Increment(Duration),
+    /// The clock only starts running once this much of the turn has passed.
+    SimpleDelay(Duration),
+    /// Time used on a move is refunded up to this much.
+    Bronstein(Duration),
+}
+
+/// Base time per player plus a per-move bonus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub bonus: Bonus,
+}
+
+impl TimeControl {
+    pub const fn new(base_secs: u64, bonus: Bonus) -> Self {
+        TimeControl { base: Duration::from_secs(base_secs), bonus }
+    }
+
+    /// `minutes`+`increment` seconds, the usual way time controls are quoted.
+    pub const fn fischer(minutes: u64, increment_secs: u64) -> Self {
+        TimeControl::new(minutes * 60, Bonus::Increment(Duration::from_secs(increment_secs)))
+    }
+
+    /// Named presets, fastest first.
+    pub const PRESETS: [(&'static str, TimeControl); 8] = [
+        ("Bullet", TimeControl::fischer(1, 0)),
+        ("Bullet", TimeControl::fischer(2, 1)),
+        ("Blitz", TimeControl::fischer(3, 2)),
+        ("Blitz", TimeControl::fischer(5, 0)),
+        ("Blitz", TimeControl::new(5 * 60, Bonus::SimpleDelay(Duration::from_secs(3)))),
+        ("Blitz", TimeControl::new(5 * 60, Bonus::Bronstein(Duration::from_secs(3)))),
+        ("Rapid", TimeControl::fischer(10, 5)),
+        ("Rapid", TimeControl::fischer(15, 10)),
+    ];
+
+    /// The increment the engine can count on when budgeting its time.
+    pub fn increment(&self) -> Duration {
+        match self.bonus {
+            Bonus::Increment(d) | Bonus::SimpleDelay(d) | Bonus::Bronstein(d) => d,
+        }
+    }
+}
+
+/// Written as `3+2` (increment), `5 d3` (simple delay) or `5 b3`
+/// (Bronstein), with the base in minutes and the bonus in seconds.
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.base.as_secs();
+        if secs.is_multiple_of(60) {
+            write!(f, "{}", secs / 60)?;
+        } else {
+            write!(f, "{}", secs as f64 / 60.0)?;
+        }
+        match self.bonus {
+            Bonus::Increment(d) => write!(f, "+{}", d.as_secs()),
+            Bonus::SimpleDelay(d) => write!(f, " d{}", d.as_secs()),
+            Bonus::Bronstein(d) => write!(f, " b{}", d.as_secs()),
+        }
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.find(['+', ' ', 'd', 'b']).unwrap_or(s.len());
+        let (base, rest) = s.split_at(split);
+        let minutes: f64 = base
+            .parse()
+            .ok()
+            .filter(|&m: &f64| m > 0.0 && m <= 600.0)
+            .ok_or_else(|| format!("base time '{base}' should be minutes, e.g. 3+2, 5 d3 or 5 b3"))?;
+
+        let rest = rest.trim_start_matches([' ', '+']);
+        let (kind, secs) = match rest.chars().next() {
+            Some(c @ ('d' | 'b')) => (Some(c), &rest[1..]),
+            _ => (None, rest),
+        };
+        let secs: u64 = if secs.is_empty() {
+            0
+        } else {
+            secs.trim().parse().map_err(|_| format!("bonus '{secs}' should be whole seconds"))?
+        };
+        let bonus = Duration::from_secs(secs);
+        Ok(TimeControl {
+            base: Duration::from_secs_f64(minutes * 60.0),
+            bonus: match kind {
+                Some('d') => Bonus::SimpleDelay(bonus),
+                Some('b') => Bonus::Bronstein(bonus),
+                _ => Bonus::Increment(bonus),
+            },
+        })
+    }
+}
+
+/// A two-sided game clock. The caller advances whichever side is to move
+/// with `tick` and calls `press` when that side completes a move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChessClock {
+    control: TimeControl,
+    /// Indexed by `Color::to_index`.
+    remaining: [Duration; 2],
+    /// Time spent on the move in progress.
+    turn: Duration,
+}
+
+impl ChessClock {
+    pub fn new(control: TimeControl) -> Self {
+        ChessClock { control, remaining: [control.base; 2], turn: Duration::ZERO }
+    }
+
+    pub fn control(&self) -> &TimeControl {
+        &self.control
+    }
+
+    pub fn remaining(&self, color: ChessColor) -> Duration {
+        self.remaining[color.to_index()]
+    }
+
+    /// Sets `color`'s time left, e.g. when resuming a saved game.
+    pub fn set_remaining(&mut self, color: ChessColor, time: Duration) {
+        self.remaining[color.to_index()] = time;
+    }
+
+    /// Runs `color`'s clock for `elapsed`.
+    pub fn tick(&mut self, color: ChessColor, elapsed: Duration) {
+        let before = self.turn;
+        self.turn += elapsed;
+        let charged = match self.control.bonus {
+            Bonus::SimpleDelay(delay) => self.turn.saturating_sub(delay) - before.saturating_sub(delay),
+            _ => elapsed,
+        };
+        let left = &mut self.remaining[color.to_index()];
+        *left = left.saturating_sub(charged);
+    }
+
+    /// `color` has completed a move: apply the bonus and start a new turn.
+    /// A flag that has already fallen stays down.
+    pub fn press(&mut self, color: ChessColor) {
+        let left = &mut self.remaining[color.to_index()];
+        if !left.is_zero() {
+            *left += match self.control.bonus {
+                Bonus::Increment(increment) => increment,
+                Bonus::Bronstein(delay) => self.turn.min(delay),
+                Bonus::SimpleDelay(_) => Duration::ZERO,
+            };
+        }
+        self.turn = Duration::ZERO;
+    }
+
+    /// The side whose time has run out, if any.
+    pub fn flagged(&self) -> Option<ChessColor> {
+        [ChessColor::White, ChessColor::Black].into_iter().find(|&c| self.remaining(c).is_zero())
+    }
+}
+
+/// Clock face text: `m:ss`, or `s.t` in the last twenty seconds.
+pub fn format_clock(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+    if time < Duration::from_secs(20) {
+        format!("{}.{}", tenths / 10, tenths % 10)
+    } else {
+        let secs = time.as_secs();
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
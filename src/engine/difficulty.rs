@@ -0,0 +1,157 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::str::FromStr;
+
+use chess::ChessMove;
+use rand::Rng;
+
+use crate::engine::book::OpeningBook;
+use crate::engine::search::{time_budget_ms, SearchFeatures, SearchLimits};
+use crate::engine::GameRecord;
+
+/// How many of the next-best root moves a deliberate mistake is drawn from.
+const SUBOPTIMAL_POOL: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// Knobs that turn a difficulty level into engine behaviour.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrengthProfile {
+    /// Maximum search depth in plies.
+    pub depth: i32,
+    /// Thinking time the engine may spend per move.
+    pub time_ms: u64,
+    /// Root scores are perturbed by up to this many centipawns either way.
+    pub eval_noise: i32,
+    /// Chance of deliberately playing one of the next-best root moves.
+    pub blunder_chance: f64,
+    /// Plies from the start of the game during which book moves are played.
+    pub book_plies: usize,
+    /// How far from the book's main lines the engine strays; see
+    /// `OpeningBook::pick`.
+    pub book_variety: f64,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn profile(self) -> StrengthProfile {
+        match self {
+            Difficulty::Easy => StrengthProfile {
+                depth: 2,
+                time_ms: 250,
+                eval_noise: 150,
+                blunder_chance: 0.3,
+                book_plies: 6,
+                book_variety: 2.0,
+            },
+            Difficulty::Medium => StrengthProfile {
+                depth: 3,
+                time_ms: 750,
+                eval_noise: 40,
+                blunder_chance: 0.08,
+                book_plies: 12,
+                book_variety: 1.0,
+            },
+            Difficulty::Hard => StrengthProfile {
+                depth: 5,
+                time_ms: 2000,
+                eval_noise: 0,
+                blunder_chance: 0.0,
+                book_plies: 24,
+                book_variety: 0.5,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        })
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL.into_iter().find(|d| d.to_string().eq_ignore_ascii_case(s)).ok_or(())
+    }
+}
+
+impl StrengthProfile {
+    /// Search budget for this profile. Root scores only need to be exact when
+    /// the profile may pick something other than the best move.
+    pub fn limits(&self) -> SearchLimits {
+        SearchLimits {
+            depth: Some(self.depth),
+            time_ms: Some(self.time_ms),
+            nodes: None,
+            exact_root_scores: self.handicapped(),
+            multi_pv: 0,
+            features: SearchFeatures::default(),
+        }
+    }
+
+    /// Search budget when playing on a clock: the thinking time comes from
+    /// what is left and the increment rather than the profile. Handicapped
+    /// levels keep their depth cap, since that is part of what makes them
+    /// weaker; full strength searches as deep as the time allows.
+    pub fn clock_limits(&self, remaining_ms: u64, increment_ms: u64) -> SearchLimits {
+        SearchLimits {
+            depth: self.handicapped().then_some(self.depth),
+            time_ms: Some(time_budget_ms(remaining_ms, increment_ms, None)),
+            ..self.limits()
+        }
+    }
+
+    /// A move from `book` for the current position of `game`, while the
+    /// game is still young enough for this profile to play from the book.
+    pub fn book_move<R: Rng + ?Sized>(&self, book: &OpeningBook, game: &GameRecord, rng: &mut R) -> Option<ChessMove> {
+        if game.len() >= self.book_plies {
+            return None;
+        }
+        book.pick(&game.board(), self.book_variety, rng)
+    }
+
+    fn handicapped(&self) -> bool {
+        self.eval_noise > 0 || self.blunder_chance > 0.0
+    }
+}
+
+/// Pick a move from root moves scored by the search, applying the profile's
+/// noise and occasional deliberate mistakes. Returns `None` if `scored` is empty.
+pub fn pick_move<R: Rng + ?Sized>(
+    scored: &[(ChessMove, i32)],
+    profile: &StrengthProfile,
+    rng: &mut R,
+) -> Option<ChessMove> {
+    let mut noisy: Vec<(ChessMove, i32)> = scored
+        .iter()
+        .map(|&(mv, score)| {
+            let noise = if profile.eval_noise > 0 {
+                rng.gen_range(-profile.eval_noise..=profile.eval_noise)
+            } else {
+                0
+            };
+            (mv, score.saturating_add(noise))
+        })
+        .collect();
+    noisy.sort_by_key(|&(_, score)| Reverse(score));
+
+    if noisy.len() > 1 && profile.blunder_chance > 0.0 && rng.gen_bool(profile.blunder_chance) {
+        let pool = noisy.len().min(SUBOPTIMAL_POOL + 1);
+        return Some(noisy[rng.gen_range(1..pool)].0);
+    }
+
+    noisy.first().map(|&(mv, _)| mv)
+}
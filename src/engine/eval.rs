@@ -0,0 +1,316 @@
+use chess::{
+    get_adjacent_files, get_bishop_moves, get_file, get_king_moves, get_knight_moves,
+    get_rook_moves, BitBoard, Board, Color as ChessColor, Piece, Square, EMPTY,
+};
+
+/// Total game phase when every minor, rook and queen is still on the board.
+const MAX_PHASE: i32 = 24;
+
+const MG_VALUE: [i32; 6] = [100, 320, 330, 500, 900, 0];
+const EG_VALUE: [i32; 6] = [120, 300, 320, 520, 950, 0];
+const PHASE_WEIGHT: [i32; 6] = [0, 1, 1, 2, 4, 0];
+
+// Piece-square tables are written from White's point of view with rank 8
+// on the first row, so they read like a diagram.
+#[rustfmt::skip]
+const PAWN_MG: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const PAWN_EG: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    80, 80, 80, 80, 80, 80, 80, 80,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    30, 30, 30, 30, 30, 30, 30, 30,
+    20, 20, 20, 20, 20, 20, 20, 20,
+    10, 10, 10, 10, 10, 10, 10, 10,
+     5,  5,  5,  5,  5,  5,  5,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT: [i32; 64] = [
+   -50,-40,-30,-30,-30,-30,-40,-50,
+   -40,-20,  0,  0,  0,  0,-20,-40,
+   -30,  0, 10, 15, 15, 10,  0,-30,
+   -30,  5, 15, 20, 20, 15,  5,-30,
+   -30,  0, 15, 20, 20, 15,  0,-30,
+   -30,  5, 10, 15, 15, 10,  5,-30,
+   -40,-20,  0,  5,  5,  0,-20,-40,
+   -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP: [i32; 64] = [
+   -20,-10,-10,-10,-10,-10,-10,-20,
+   -10,  0,  0,  0,  0,  0,  0,-10,
+   -10,  0,  5, 10, 10,  5,  0,-10,
+   -10,  5,  5, 10, 10,  5,  5,-10,
+   -10,  0, 10, 10, 10, 10,  0,-10,
+   -10, 10, 10, 10, 10, 10, 10,-10,
+   -10,  5,  0,  0,  0,  0,  5,-10,
+   -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN: [i32; 64] = [
+   -20,-10,-10, -5, -5,-10,-10,-20,
+   -10,  0,  0,  0,  0,  0,  0,-10,
+   -10,  0,  5,  5,  5,  5,  0,-10,
+    -5,  0,  5,  5,  5,  5,  0, -5,
+     0,  0,  5,  5,  5,  5,  0, -5,
+   -10,  5,  5,  5,  5,  5,  0,-10,
+   -10,  0,  5,  0,  0,  0,  0,-10,
+   -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const KING_MG: [i32; 64] = [
+   -30,-40,-40,-50,-50,-40,-40,-30,
+   -30,-40,-40,-50,-50,-40,-40,-30,
+   -30,-40,-40,-50,-50,-40,-40,-30,
+   -30,-40,-40,-50,-50,-40,-40,-30,
+   -20,-30,-30,-40,-40,-30,-30,-20,
+   -10,-20,-20,-20,-20,-20,-20,-10,
+    20, 20,  0,  0,  0,  0, 20, 20,
+    20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+#[rustfmt::skip]
+const KING_EG: [i32; 64] = [
+   -50,-40,-30,-20,-20,-30,-40,-50,
+   -30,-20,-10,  0,  0,-10,-20,-30,
+   -30,-10, 20, 30, 30, 20,-10,-30,
+   -30,-10, 30, 40, 40, 30,-10,-30,
+   -30,-10, 30, 40, 40, 30,-10,-30,
+   -30,-10, 20, 30, 30, 20,-10,-30,
+   -30,-30,  0,  0,  0,  0,-30,-30,
+   -50,-30,-30,-30,-30,-30,-30,-50,
+];
+
+const DOUBLED_PAWN: (i32, i32) = (-15, -25);
+const ISOLATED_PAWN: (i32, i32) = (-12, -18);
+const PASSED_PAWN_MG: [i32; 8] = [0, 5, 10, 15, 25, 40, 60, 0];
+const PASSED_PAWN_EG: [i32; 8] = [0, 10, 20, 35, 60, 90, 130, 0];
+const BISHOP_PAIR: (i32, i32) = (30, 50);
+
+// Per-square mobility weights and the "typical" number of squares a piece
+// reaches, so an average piece scores zero.
+const MOBILITY_MG: [i32; 6] = [0, 4, 5, 2, 1, 0];
+const MOBILITY_EG: [i32; 6] = [0, 4, 5, 4, 2, 0];
+const MOBILITY_BASE: [i32; 6] = [0, 4, 6, 7, 13, 0];
+
+const SHIELD_NEAR: i32 = 10;
+const SHIELD_FAR: i32 = 5;
+const KING_ATTACK_WEIGHT: [i32; 6] = [0, 2, 2, 3, 5, 0];
+
+/// Material value of a piece in centipawns, as used by the middlegame score.
+pub fn piece_value(piece: Piece) -> i32 {
+    MG_VALUE[piece.to_index()]
+}
+
+/// Static evaluation of `board` in centipawns from White's point of view.
+///
+/// Combines material, piece-square tables, pawn structure, mobility and king
+/// safety, tapered between middlegame and endgame weights by the remaining
+/// non-pawn material.
+pub fn evaluate(board: &Board) -> i32 {
+    let mut mg = 0;
+    let mut eg = 0;
+    let mut phase = 0;
+
+    for color in [ChessColor::White, ChessColor::Black] {
+        let sign = if color == ChessColor::White { 1 } else { -1 };
+        let (side_mg, side_eg, side_phase) = evaluate_side(board, color);
+        mg += sign * side_mg;
+        eg += sign * side_eg;
+        phase += side_phase;
+    }
+
+    let phase = phase.min(MAX_PHASE);
+    (mg * phase + eg * (MAX_PHASE - phase)) / MAX_PHASE
+}
+
+fn evaluate_side(board: &Board, color: ChessColor) -> (i32, i32, i32) {
+    let own = *board.color_combined(color);
+    let mut mg = 0;
+    let mut eg = 0;
+    let mut phase = 0;
+
+    for piece in [Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen, Piece::King] {
+        let idx = piece.to_index();
+        for sq in *board.pieces(piece) & own {
+            let pst = pst_index(sq, color);
+            let (pst_mg, pst_eg) = match piece {
+                Piece::Pawn => (PAWN_MG[pst], PAWN_EG[pst]),
+                Piece::Knight => (KNIGHT[pst], KNIGHT[pst]),
+                Piece::Bishop => (BISHOP[pst], BISHOP[pst]),
+                Piece::Rook => (ROOK[pst], ROOK[pst]),
+                Piece::Queen => (QUEEN[pst], QUEEN[pst]),
+                Piece::King => (KING_MG[pst], KING_EG[pst]),
+            };
+            mg += MG_VALUE[idx] + pst_mg;
+            eg += EG_VALUE[idx] + pst_eg;
+            phase += PHASE_WEIGHT[idx];
+        }
+    }
+
+    if (*board.pieces(Piece::Bishop) & own).popcnt() >= 2 {
+        mg += BISHOP_PAIR.0;
+        eg += BISHOP_PAIR.1;
+    }
+
+    let (pawn_mg, pawn_eg) = pawn_structure(board, color);
+    let (mob_mg, mob_eg) = mobility(board, color);
+    mg += pawn_mg + mob_mg + king_safety(board, color);
+    eg += pawn_eg + mob_eg;
+
+    (mg, eg, phase)
+}
+
+/// Index into a diagram-ordered table for a piece of `color` on `sq`.
+fn pst_index(sq: Square, color: ChessColor) -> usize {
+    let rank = sq.get_rank().to_index();
+    let file = sq.get_file().to_index();
+    match color {
+        ChessColor::White => (7 - rank) * 8 + file,
+        ChessColor::Black => rank * 8 + file,
+    }
+}
+
+/// Rank of `sq` counted from `color`'s own back rank.
+fn relative_rank(sq: Square, color: ChessColor) -> usize {
+    match color {
+        ChessColor::White => sq.get_rank().to_index(),
+        ChessColor::Black => 7 - sq.get_rank().to_index(),
+    }
+}
+
+/// All squares strictly in front of `sq` from `color`'s point of view.
+fn forward_ranks(sq: Square, color: ChessColor) -> BitBoard {
+    let rank = sq.get_rank().to_index();
+    match color {
+        ChessColor::White if rank < 7 => BitBoard(!0u64 << (8 * (rank + 1))),
+        ChessColor::Black => BitBoard((1u64 << (8 * rank)) - 1),
+        _ => EMPTY,
+    }
+}
+
+fn pawn_structure(board: &Board, color: ChessColor) -> (i32, i32) {
+    let pawns = *board.pieces(Piece::Pawn);
+    let own = pawns & *board.color_combined(color);
+    let enemy = pawns & *board.color_combined(!color);
+    let mut mg = 0;
+    let mut eg = 0;
+
+    for sq in own {
+        let file = sq.get_file();
+        let file_mask = get_file(file);
+        let adjacent = get_adjacent_files(file);
+
+        if (own & file_mask & forward_ranks(sq, color)) != EMPTY {
+            mg += DOUBLED_PAWN.0;
+            eg += DOUBLED_PAWN.1;
+        }
+        if (own & adjacent) == EMPTY {
+            mg += ISOLATED_PAWN.0;
+            eg += ISOLATED_PAWN.1;
+        }
+        if (enemy & (file_mask | adjacent) & forward_ranks(sq, color)) == EMPTY {
+            let rank = relative_rank(sq, color);
+            mg += PASSED_PAWN_MG[rank];
+            eg += PASSED_PAWN_EG[rank];
+        }
+    }
+
+    (mg, eg)
+}
+
+fn mobility(board: &Board, color: ChessColor) -> (i32, i32) {
+    let own = *board.color_combined(color);
+    let blockers = *board.combined();
+    let mut mg = 0;
+    let mut eg = 0;
+
+    for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+        let idx = piece.to_index();
+        for sq in *board.pieces(piece) & own {
+            let reach = (attacks(piece, sq, blockers) & !own).popcnt() as i32 - MOBILITY_BASE[idx];
+            mg += reach * MOBILITY_MG[idx];
+            eg += reach * MOBILITY_EG[idx];
+        }
+    }
+
+    (mg, eg)
+}
+
+fn attacks(piece: Piece, sq: Square, blockers: BitBoard) -> BitBoard {
+    match piece {
+        Piece::Knight => get_knight_moves(sq),
+        Piece::Bishop => get_bishop_moves(sq, blockers),
+        Piece::Rook => get_rook_moves(sq, blockers),
+        Piece::Queen => get_bishop_moves(sq, blockers) | get_rook_moves(sq, blockers),
+        Piece::King => get_king_moves(sq),
+        Piece::Pawn => EMPTY,
+    }
+}
+
+/// Middlegame-only king safety: pawn shield in front of a castled king and a
+/// penalty for enemy pieces bearing down on the squares around it.
+fn king_safety(board: &Board, color: ChessColor) -> i32 {
+    let king_sq = board.king_square(color);
+    let own_pawns = *board.pieces(Piece::Pawn) & *board.color_combined(color);
+    let mut score = 0;
+
+    if relative_rank(king_sq, color) <= 1 {
+        let shield_files = get_file(king_sq.get_file()) | get_adjacent_files(king_sq.get_file());
+        for pawn in own_pawns & shield_files & forward_ranks(king_sq, color) {
+            match relative_rank(pawn, color) - relative_rank(king_sq, color) {
+                1 => score += SHIELD_NEAR,
+                2 => score += SHIELD_FAR,
+                _ => {}
+            }
+        }
+    }
+
+    let zone = get_king_moves(king_sq) | BitBoard::from_square(king_sq);
+    let enemy = *board.color_combined(!color);
+    let blockers = *board.combined();
+    let mut attackers = 0;
+    let mut weight = 0;
+    for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+        for sq in *board.pieces(piece) & enemy {
+            let hits = (attacks(piece, sq, blockers) & zone).popcnt() as i32;
+            if hits > 0 {
+                attackers += 1;
+                weight += hits * KING_ATTACK_WEIGHT[piece.to_index()];
+            }
+        }
+    }
+    // A lone attacker is rarely dangerous; scale up once several pieces join in.
+    if attackers >= 2 {
+        score -= weight * attackers * 2;
+    }
+
+    score
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chess::Board;
+
+/// Why a FEN string was rejected, worded for the person who typed it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FenError(pub String);
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Parses a FEN string. The move counters may be left off, and every
+/// problem the `chess` crate would only report as "invalid FEN" is spelled
+/// out.
+pub fn parse_fen(text: &str) -> Result<Board, FenError> {
+    let err = |msg: String| Err(FenError(msg));
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    if fields.is_empty() {
+        return err("FEN is empty".to_string());
+    }
+    if fields.len() > 6 {
+        return err(format!("expected at most 6 space-separated fields, found {}", fields.len()));
+    }
+
+    let ranks: Vec<&str> = fields[0].split('/').collect();
+    if ranks.len() != 8 {
+        return err(format!("piece placement needs 8 ranks, found {}", ranks.len()));
+    }
+    for (i, rank) in ranks.iter().enumerate() {
+        let mut squares = 0;
+        for c in rank.chars() {
+            match c {
+                '1'..='8' => squares += c as u32 - '0' as u32,
+                'p' | 'n' | 'b' | 'r' | 'q' | 'k' | 'P' | 'N' | 'B' | 'R' | 'Q' | 'K' => squares += 1,
+                _ => return err(format!("unknown piece '{c}' on rank {}", 8 - i)),
+            }
+        }
+        if squares != 8 {
+            return err(format!("rank {} has {squares} squares instead of 8", 8 - i));
+        }
+    }
+    for (king, side) in [('K', "White"), ('k', "Black")] {
+        let count = fields[0].matches(king).count();
+        if count != 1 {
+            return err(format!("{side} needs exactly one king, found {count}"));
+        }
+    }
+
+    if ranks[0].contains(['p', 'P']) || ranks[7].contains(['p', 'P']) {
+        return err("pawns cannot stand on the first or last rank".to_string());
+    }
+
+    // Missing fields default to White to move with no castling rights.
+    let side = fields.get(1).copied().unwrap_or("w");
+    if side != "w" && side != "b" {
+        return err(format!("side to move must be 'w' or 'b', not '{side}'"));
+    }
+    let castling = fields.get(2).copied().unwrap_or("-");
+    if castling != "-" && !castling.chars().all(|c| "KQkq".contains(c)) {
+        return err(format!("castling rights '{castling}' should be '-' or a mix of KQkq"));
+    }
+    let en_passant = fields.get(3).copied().unwrap_or("-");
+    let ep_ok = en_passant == "-"
+        || matches!(en_passant.as_bytes(), [b'a'..=b'h', b'3' | b'6']);
+    if !ep_ok {
+        return err(format!("en passant square '{en_passant}' is not on the 3rd or 6th rank"));
+    }
+    for (name, value) in [("halfmove clock", fields.get(4)), ("fullmove number", fields.get(5))] {
+        if let Some(value) = value {
+            if value.parse::<u32>().is_err() {
+                return err(format!("{name} '{value}' is not a number"));
+            }
+        }
+    }
+
+    let full = format!(
+        "{} {side} {castling} {en_passant} {} {}",
+        fields[0],
+        fields.get(4).unwrap_or(&"0"),
+        fields.get(5).unwrap_or(&"1")
+    );
+    Board::from_str(&full).or_else(|_| {
+        err("illegal position: castling or en passant rights do not match the pieces, or the side not to move is in check"
+            .to_string())
+    })
+}
+
+/// The halfmove clock field of a FEN, or 0 when it is left off.
+pub fn halfmove_clock(text: &str) -> u32 {
+    text.split_whitespace().nth(4).and_then(|n| n.parse().ok()).unwrap_or(0)
+}
+
+/// The fullmove number field of a FEN, or 1 when it is left off.
+pub fn fullmove_number(text: &str) -> u32 {
+    text.split_whitespace().nth(5).and_then(|n| n.parse().ok()).unwrap_or(1)
+}
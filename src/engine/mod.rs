@@ -0,0 +1,143 @@
+//! Search, evaluation and game bookkeeping, free of any UI dependency so it
+//! can be used from the GUI, the UCI binary and tests alike.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use chess::Board;
+
+pub mod bench;
+pub mod book;
+pub mod clock;
+pub mod difficulty;
+pub mod eval;
+pub mod fen;
+pub mod ordering;
+pub mod perft;
+pub mod pgn;
+pub mod record;
+pub mod rules;
+pub mod savegame;
+pub mod san;
+pub mod search;
+pub mod see;
+pub mod syzygy;
+pub mod tree;
+pub mod tt;
+pub mod worker;
+
+pub use difficulty::{Difficulty, StrengthProfile};
+pub use record::GameRecord;
+pub use search::{SearchFeatures, SearchLimits, SearchResult};
+
+use search::Search;
+use syzygy::Tablebase;
+use tt::{TranspositionTable, TtStats};
+
+/// A position to search plus the transposition table shared by successive
+/// searches.
+pub struct Engine {
+    board: Board,
+    /// Earlier positions `board` may repeat, oldest first.
+    history: Vec<u64>,
+    halfmove_clock: u32,
+    tt: TranspositionTable,
+    tablebase: Option<Arc<Tablebase>>,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Engine::with_hash_mb(tt::DEFAULT_SIZE_MB)
+    }
+
+    pub fn with_hash_mb(size_mb: usize) -> Self {
+        Engine {
+            board: Board::default(),
+            history: Vec::new(),
+            halfmove_clock: 0,
+            tt: TranspositionTable::new(size_mb),
+            tablebase: None,
+        }
+    }
+
+    /// Reallocates the transposition table, dropping its contents.
+    pub fn set_hash_mb(&mut self, size_mb: usize) {
+        self.tt.resize(size_mb);
+    }
+
+    /// Endgame tables to play from and score with, or `None` to search
+    /// without them. Scores found with the old setting no longer hold, so
+    /// the transposition table is cleared.
+    pub fn set_tablebase(&mut self, tablebase: Option<Tablebase>) {
+        self.tablebase = tablebase.map(Arc::new);
+        self.tt.clear();
+    }
+
+    /// Forgets everything learned from previous searches and returns to the
+    /// start position.
+    pub fn new_game(&mut self) {
+        self.tt.clear();
+        self.set_position(Board::default());
+    }
+
+    /// Sets a position with no game history behind it.
+    pub fn set_position(&mut self, board: Board) {
+        self.board = board;
+        self.history.clear();
+        self.halfmove_clock = 0;
+    }
+
+    /// Sets the current position of `record`, remembering its earlier
+    /// positions so the search treats returning to them as a draw.
+    pub fn set_game(&mut self, record: &GameRecord) {
+        self.board = record.board();
+        self.history = record.reversible_history();
+        self.halfmove_clock = record.halfmove_clock();
+    }
+
+    pub fn position(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn search(&mut self, limits: SearchLimits) -> SearchResult {
+        self.search_with(limits, None, |_| {})
+    }
+
+    /// Searches the current position, calling `on_iteration` each time an
+    /// iteration completes. Raising `stop` ends the search early with the
+    /// result of the last completed iteration.
+    pub fn search_with(
+        &mut self,
+        limits: SearchLimits,
+        stop: Option<&AtomicBool>,
+        mut on_iteration: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        let mut search = Search::with_history(&self.board, &self.history, limits, &mut self.tt);
+        if let Some(tablebase) = &self.tablebase {
+            search.use_tablebase(tablebase.clone(), self.halfmove_clock);
+        }
+        let mut reported = 0;
+        // Step one root move at a time so each finished iteration is seen.
+        while !search.step(&mut self.tt, stop, Some(web_time::Duration::ZERO)) {
+            if search.result().depth > reported {
+                reported = search.result().depth;
+                on_iteration(search.result());
+            }
+        }
+        let result = search.into_result();
+        if result.depth > reported {
+            on_iteration(&result);
+        }
+        result
+    }
+
+    pub fn tt_stats(&self) -> TtStats {
+        self.tt.stats()
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::new()
+    }
+}
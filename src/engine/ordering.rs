@@ -0,0 +1,137 @@
+//! Move ordering for the search. Alpha-beta cuts off sooner the earlier it
+//! meets the best move, so moves are tried in this order:
+//!
+//! 1. the hash move from the transposition table,
+//! 2. captures and promotions that do not lose material by static
+//!    exchange evaluation, most valuable victim first and, among those,
+//!    least valuable attacker first (MVV-LVA),
+//! 3. the two killer moves: quiet moves that caused a cutoff at the same
+//!    ply elsewhere in the tree,
+//! 4. other quiet moves by their history score, which grows each time the
+//!    move causes a cutoff,
+//! 5. captures that lose material.
+
+use chess::{Board, ChessMove, Piece};
+
+use crate::engine::see::see;
+
+const HASH_MOVE: i32 = i32::MAX;
+const CAPTURE: i32 = 2_000_000;
+const FIRST_KILLER: i32 = 1_000_001;
+const SECOND_KILLER: i32 = 1_000_000;
+const LOSING_CAPTURE: i32 = -1_000_000;
+/// History scores are kept below the killers by halving them all when one
+/// gets this big.
+const MAX_HISTORY: i32 = 500_000;
+
+/// Ordering values of the pieces, pawn to king.
+const VALUES: [i32; 6] = [1, 3, 3, 5, 9, 20];
+
+/// Killer moves and history scores gathered while searching. One orderer
+/// lasts a whole iterative deepening search, so what earlier iterations
+/// learned orders the later ones.
+#[derive(Clone, Debug)]
+pub struct MoveOrderer {
+    /// Two killers per ply, the most recent first.
+    killers: Vec<[Option<ChessMove>; 2]>,
+    /// Indexed by origin and target square.
+    history: Vec<i32>,
+}
+
+impl MoveOrderer {
+    pub fn new() -> Self {
+        MoveOrderer { killers: Vec::new(), history: vec![0; 64 * 64] }
+    }
+
+    /// Sorts `moves` best first for the node `ply` plies below the root.
+    pub fn order(
+        &self,
+        board: &Board,
+        mut moves: Vec<ChessMove>,
+        hash_move: Option<ChessMove>,
+        ply: usize,
+    ) -> Vec<ChessMove> {
+        let killers = self.killers.get(ply).copied().unwrap_or_default();
+        moves.sort_by_cached_key(|&mv| {
+            let score = if Some(mv) == hash_move {
+                HASH_MOVE
+            } else if is_tactical(board, mv) {
+                let base = if see(board, mv) >= 0 { CAPTURE } else { LOSING_CAPTURE };
+                base + mvv_lva(board, mv)
+            } else if Some(mv) == killers[0] {
+                FIRST_KILLER
+            } else if Some(mv) == killers[1] {
+                SECOND_KILLER
+            } else {
+                self.history[history_index(mv)]
+            };
+            std::cmp::Reverse(score)
+        });
+        moves
+    }
+
+    /// Notes that quiet move `mv` caused a beta cutoff `depth` plies from
+    /// the horizon, `ply` plies below the root. Captures are left alone:
+    /// MVV-LVA already puts them first.
+    pub fn record_cutoff(&mut self, board: &Board, mv: ChessMove, depth: i32, ply: usize) {
+        if is_tactical(board, mv) {
+            return;
+        }
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, [None; 2]);
+        }
+        let killers = &mut self.killers[ply];
+        if killers[0] != Some(mv) {
+            killers[1] = killers[0];
+            killers[0] = Some(mv);
+        }
+
+        let entry = &mut self.history[history_index(mv)];
+        *entry += depth * depth;
+        if *entry > MAX_HISTORY {
+            self.history.iter_mut().for_each(|score| *score /= 2);
+        }
+    }
+}
+
+impl Default for MoveOrderer {
+    fn default() -> Self {
+        MoveOrderer::new()
+    }
+}
+
+/// Sorts captures and promotions by MVV-LVA alone, for the quiescence
+/// search where there are no killers or hash moves to consult.
+pub fn order_captures(board: &Board, mut moves: Vec<ChessMove>) -> Vec<ChessMove> {
+    moves.sort_by_cached_key(|&mv| std::cmp::Reverse(mvv_lva(board, mv)));
+    moves
+}
+
+/// Captures and promotions, including en passant.
+pub fn is_tactical(board: &Board, mv: ChessMove) -> bool {
+    captured(board, mv).is_some() || mv.get_promotion().is_some()
+}
+
+/// Higher for taking a bigger piece with a smaller one; a promotion counts
+/// as capturing the piece promoted to.
+pub fn mvv_lva(board: &Board, mv: ChessMove) -> i32 {
+    let victim = captured(board, mv).map_or(0, value) + mv.get_promotion().map_or(0, value);
+    let attacker = board.piece_on(mv.get_source()).map_or(0, value);
+    victim * 100 - attacker
+}
+
+/// The piece `mv` takes. Only en passant lands on an empty square.
+fn captured(board: &Board, mv: ChessMove) -> Option<Piece> {
+    board.piece_on(mv.get_dest()).or_else(|| {
+        let pawn = board.piece_on(mv.get_source()) == Some(Piece::Pawn);
+        (pawn && mv.get_source().get_file() != mv.get_dest().get_file()).then_some(Piece::Pawn)
+    })
+}
+
+fn value(piece: Piece) -> i32 {
+    VALUES[piece.to_index()]
+}
+
+fn history_index(mv: ChessMove) -> usize {
+    mv.get_source().to_index() * 64 + mv.get_dest().to_index()
+}
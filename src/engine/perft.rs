@@ -0,0 +1,28 @@
+use chess::{Board, ChessMove, MoveGen};
+
+/// Number of leaf nodes of the legal move tree `depth` plies below `board`.
+///
+/// Comparing against published counts catches move generation bugs, and any
+/// board representation change should leave these numbers untouched.
+pub fn perft(board: &Board, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = MoveGen::new_legal(board);
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves.map(|mv| perft(&board.make_move_new(mv), depth - 1)).sum()
+}
+
+/// Perft split by root move, for narrowing down which move is miscounted.
+pub fn divide(board: &Board, depth: u32) -> Vec<(ChessMove, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    let mut counts: Vec<(ChessMove, u64)> = MoveGen::new_legal(board)
+        .map(|mv| (mv, perft(&board.make_move_new(mv), depth - 1)))
+        .collect();
+    counts.sort_by_key(|&(mv, _)| mv.to_string());
+    counts
+}
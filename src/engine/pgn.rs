@@ -0,0 +1,384 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chess::{Board, BoardStatus, Color as ChessColor};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::engine::fen::{fullmove_number, halfmove_clock, parse_fen};
+use crate::engine::record::GameRecord;
+use crate::engine::rules::Outcome;
+use crate::engine::san::{parse_san, to_san};
+
+/// Movetext lines are wrapped before this many characters.
+const LINE_WIDTH: usize = 80;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Ongoing,
+}
+
+impl GameResult {
+    /// Result implied by the final position alone: mate or stalemate.
+    pub fn from_board(board: &Board) -> Self {
+        match board.status() {
+            BoardStatus::Checkmate if board.side_to_move() == ChessColor::White => GameResult::BlackWins,
+            BoardStatus::Checkmate => GameResult::WhiteWins,
+            BoardStatus::Stalemate => GameResult::Draw,
+            BoardStatus::Ongoing => GameResult::Ongoing,
+        }
+    }
+
+    /// Result for a game that ended with `outcome`, or is still going on.
+    pub fn from_outcome(outcome: Option<Outcome>) -> Self {
+        match outcome {
+            Some(Outcome::Checkmate { winner } | Outcome::Timeout { winner }) if winner == ChessColor::White => {
+                GameResult::WhiteWins
+            }
+            Some(Outcome::Checkmate { .. } | Outcome::Timeout { .. }) => GameResult::BlackWins,
+            Some(Outcome::Stalemate | Outcome::Draw(_)) => GameResult::Draw,
+            None => GameResult::Ongoing,
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Ongoing => "*",
+        })
+    }
+}
+
+impl FromStr for GameResult {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1-0" => Ok(GameResult::WhiteWins),
+            "0-1" => Ok(GameResult::BlackWins),
+            "1/2-1/2" => Ok(GameResult::Draw),
+            "*" => Ok(GameResult::Ongoing),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The PGN Seven Tag Roster.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnTags {
+    pub event: String,
+    pub site: String,
+    pub date: String,
+    pub round: String,
+    pub white: String,
+    pub black: String,
+    pub result: GameResult,
+    /// Further tags, written after the roster in this order.
+    pub extra: Vec<(String, String)>,
+}
+
+impl PgnTags {
+    /// Tags for a casual game played today.
+    pub fn new(white: impl Into<String>, black: impl Into<String>) -> Self {
+        PgnTags {
+            event: "Casual game".to_string(),
+            site: "Chess AI".to_string(),
+            date: today(),
+            round: "-".to_string(),
+            white: white.into(),
+            black: black.into(),
+            result: GameResult::Ongoing,
+            extra: Vec::new(),
+        }
+    }
+}
+
+/// Renders `record` as a PGN game. A non-standard start position is
+/// recorded with the `SetUp` and `FEN` tags.
+pub fn export(record: &GameRecord, tags: &PgnTags) -> String {
+    let mut out = String::new();
+    for (name, value) in [
+        ("Event", tags.event.as_str()),
+        ("Site", &tags.site),
+        ("Date", &tags.date),
+        ("Round", &tags.round),
+        ("White", &tags.white),
+        ("Black", &tags.black),
+        ("Result", &tags.result.to_string()),
+    ] {
+        out.push_str(&format!("[{name} \"{}\"]\n", escape(value)));
+    }
+    if *record.start() != Board::default() {
+        out.push_str("[SetUp \"1\"]\n");
+        // Board's FEN leaves the clock at 0 and the move number at 1, losing both
+        let fen = record.start().to_string();
+        let placement: Vec<&str> = fen.split_whitespace().take(4).collect();
+        out.push_str(&format!(
+            "[FEN \"{} {} {}\"]\n",
+            placement.join(" "),
+            record.halfmove_clock_at(0),
+            record.start_fullmove_number()
+        ));
+    }
+    for (name, value) in &tags.extra {
+        out.push_str(&format!("[{name} \"{}\"]\n", escape(value)));
+    }
+    out.push('\n');
+
+    let mut tokens = Vec::new();
+    let mut board = *record.start();
+    let mut number = record.start_fullmove_number();
+    for (i, &mv) in record.iter().enumerate() {
+        match board.side_to_move() {
+            ChessColor::White => tokens.push(format!("{number}.")),
+            ChessColor::Black if i == 0 => tokens.push(format!("{number}...")),
+            ChessColor::Black => {}
+        }
+        tokens.push(to_san(&board, mv));
+        if board.side_to_move() == ChessColor::Black {
+            number += 1;
+        }
+        board = board.make_move_new(mv);
+    }
+    tokens.push(tags.result.to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() >= LINE_WIDTH {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+/// A game read from PGN.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnGame {
+    /// Every tag pair, in file order.
+    pub tags: Vec<(String, String)>,
+    pub record: GameRecord,
+    pub result: GameResult,
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// Where and why a PGN file could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnError {
+    /// 1-based index of the game in the file.
+    pub game: usize,
+    /// Move number and side of the offending move, for errors in the movetext.
+    pub move_number: Option<(u32, ChessColor)>,
+    pub message: String,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.move_number {
+            Some((n, ChessColor::White)) => write!(f, "game {}, move {n}. {}", self.game, self.message),
+            Some((n, ChessColor::Black)) => write!(f, "game {}, move {n}... {}", self.game, self.message),
+            None => write!(f, "game {}: {}", self.game, self.message),
+        }
+    }
+}
+
+/// Reads every game in `text`. Comments, NAGs and variations are skipped;
+/// only the main line is kept.
+pub fn parse(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut games = Vec::new();
+    let mut game = GameBuilder::default();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '[' => {
+                chars.next();
+                // A tag after moves means the previous game lacked a result.
+                if game.board.is_some() {
+                    games.push(std::mem::take(&mut game).finish(None, games.len() + 1)?);
+                }
+                let tag: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                game.tags.push(parse_tag(&tag).ok_or_else(|| PgnError {
+                    game: games.len() + 1,
+                    move_number: None,
+                    message: format!("malformed tag [{tag}]"),
+                })?);
+            }
+            '{' => {
+                chars.by_ref().find(|&c| c == '}');
+            }
+            ';' | '%' => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '(' => {
+                let mut depth = 0;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '(' => depth += 1,
+                        ')' if depth == 1 => break,
+                        ')' => depth -= 1,
+                        '{' => {
+                            chars.by_ref().find(|&c| c == '}');
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "[]{}();".contains(c) {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                if token.is_empty() {
+                    // A stray closing bracket
+                    chars.next();
+                    continue;
+                }
+                if let Ok(result) = token.parse::<GameResult>() {
+                    games.push(std::mem::take(&mut game).finish(Some(result), games.len() + 1)?);
+                    continue;
+                }
+                game.token(&token, games.len() + 1)?;
+            }
+        }
+    }
+    if game.board.is_some() || !game.tags.is_empty() {
+        games.push(game.finish(None, games.len() + 1)?);
+    }
+    Ok(games)
+}
+
+/// Splits the inside of `[Name "value"]`.
+fn parse_tag(tag: &str) -> Option<(String, String)> {
+    let (name, rest) = tag.trim().split_once(char::is_whitespace)?;
+    let quoted = rest.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut escaped = false;
+    for c in quoted.chars() {
+        if c == '\\' && !escaped {
+            escaped = true;
+        } else {
+            value.push(c);
+            escaped = false;
+        }
+    }
+    Some((name.to_string(), value))
+}
+
+/// A game whose movetext is still being read.
+#[derive(Default)]
+struct GameBuilder {
+    tags: Vec<(String, String)>,
+    /// Current position, set up from the tags at the first move.
+    board: Option<Board>,
+    record: Option<GameRecord>,
+    move_number: u32,
+}
+
+impl GameBuilder {
+    /// Sets up the start position from the `FEN` tag, if any.
+    fn start(&mut self, game: usize) -> Result<(), PgnError> {
+        if self.board.is_some() {
+            return Ok(());
+        }
+        let fen = self.tags.iter().find(|(n, _)| n == "FEN").map(|(_, v)| v.clone());
+        let board = match &fen {
+            Some(fen) => parse_fen(fen).map_err(|e| PgnError {
+                game,
+                move_number: None,
+                message: format!("invalid FEN {fen}: {e}"),
+            })?,
+            None => Board::default(),
+        };
+        let clock = fen.as_deref().map_or(0, halfmove_clock);
+        self.move_number = fen.as_deref().map_or(1, fullmove_number);
+        self.board = Some(board);
+        self.record = Some(
+            GameRecord::new(board).with_halfmove_clock(clock).with_fullmove_number(self.move_number),
+        );
+        Ok(())
+    }
+
+    fn token(&mut self, token: &str, game: usize) -> Result<(), PgnError> {
+        self.start(game)?;
+        // Move numbers ("12." or "12...") may be glued to the move.
+        let san = token.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start_matches('.');
+        let san = if san.len() < token.len() && token.contains('.') { san } else { token };
+        if san.is_empty() || san.starts_with('$') {
+            return Ok(());
+        }
+
+        let board = self.board.as_mut().unwrap();
+        let side = board.side_to_move();
+        let mv = parse_san(board, san).map_err(|e| PgnError {
+            game,
+            move_number: Some((self.move_number, side)),
+            message: format!("{san}: {e}"),
+        })?;
+        *board = board.make_move_new(mv);
+        self.record.as_mut().unwrap().push(mv);
+        if side == ChessColor::Black {
+            self.move_number += 1;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, result: Option<GameResult>, game: usize) -> Result<PgnGame, PgnError> {
+        self.start(game)?;
+        let result = result
+            .or_else(|| self.tags.iter().find(|(n, _)| n == "Result").and_then(|(_, v)| v.parse().ok()))
+            .unwrap_or(GameResult::Ongoing);
+        Ok(PgnGame { tags: self.tags, record: self.record.unwrap(), result })
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Today's date in PGN's `YYYY.MM.DD` form (UTC).
+pub fn today() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (y, m, d) = civil_from_days((secs / 86_400) as i64);
+    format!("{y:04}.{m:02}.{d:02}")
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's algorithm, counting in 400-year eras from 0000-03-01.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,204 @@
+use chess::{Board, BoardStatus, ChessMove, Color as ChessColor, Piece};
+
+use crate::engine::rules::{is_dead_position, DrawReason, Outcome};
+
+/// The moves of a game and the position they were played from, along with
+/// what `Board` does not track itself: the positions seen so far and the
+/// halfmove clock, for the repetition and move-count rules, and the move
+/// number the game started at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    start: Board,
+    moves: Vec<ChessMove>,
+    /// The position before each move and after the last, so `moves.len() + 1` long.
+    boards: Vec<Board>,
+    /// Halfmove clock for each entry of `boards`.
+    clocks: Vec<u32>,
+    /// Fullmove number of `start`.
+    start_fullmove: u32,
+}
+
+impl GameRecord {
+    pub fn new(start: Board) -> Self {
+        GameRecord { start, moves: Vec::new(), boards: vec![start], clocks: vec![0], start_fullmove: 1 }
+    }
+
+    /// Starts the halfmove clock at `clock`, as given by a FEN. Only
+    /// meaningful before any move is pushed.
+    pub fn with_halfmove_clock(mut self, clock: u32) -> Self {
+        self.clocks[0] = clock;
+        self
+    }
+
+    /// Numbers the first move `number`, as given by a FEN.
+    pub fn with_fullmove_number(mut self, number: u32) -> Self {
+        self.start_fullmove = number.max(1);
+        self
+    }
+
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    pub fn moves(&self) -> &[ChessMove] {
+        &self.moves
+    }
+
+    pub fn push(&mut self, mv: ChessMove) {
+        let board = self.board();
+        let clock = if resets_clock(&board, mv) { 0 } else { self.halfmove_clock() + 1 };
+        self.moves.push(mv);
+        self.boards.push(board.make_move_new(mv));
+        self.clocks.push(clock);
+    }
+
+    pub fn pop(&mut self) -> Option<ChessMove> {
+        let mv = self.moves.pop()?;
+        self.boards.pop();
+        self.clocks.pop();
+        Some(mv)
+    }
+
+    /// Keeps only the first `ply` moves.
+    pub fn truncate(&mut self, ply: usize) {
+        self.moves.truncate(ply);
+        self.boards.truncate(self.moves.len() + 1);
+        self.clocks.truncate(self.moves.len() + 1);
+    }
+
+    /// Drops all moves, keeping the start position.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn last(&self) -> Option<ChessMove> {
+        self.moves.last().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, ChessMove> {
+        self.moves.iter()
+    }
+
+    /// The position after every recorded move.
+    pub fn board(&self) -> Board {
+        self.board_at(self.moves.len())
+    }
+
+    /// The position after the first `ply` moves.
+    pub fn board_at(&self, ply: usize) -> Board {
+        self.boards[ply.min(self.moves.len())]
+    }
+
+    /// Halfmoves since the last capture or pawn move.
+    pub fn halfmove_clock(&self) -> u32 {
+        *self.clocks.last().unwrap()
+    }
+
+    /// The fullmove number of the start position.
+    pub fn start_fullmove_number(&self) -> u32 {
+        self.start_fullmove
+    }
+
+    /// The halfmove clock after the first `ply` moves.
+    pub fn halfmove_clock_at(&self, ply: usize) -> u32 {
+        self.clocks[ply.min(self.moves.len())]
+    }
+
+    /// How many times the current position has occurred, this time included.
+    pub fn repetitions(&self) -> usize {
+        let key = self.board().get_hash();
+        self.reversible_history().iter().filter(|&&h| h == key).count() + 1
+    }
+
+    /// Hashes of the earlier positions the current one could still repeat:
+    /// those since the last capture or pawn move, oldest first.
+    pub fn reversible_history(&self) -> Vec<u64> {
+        let current = self.boards.len() - 1;
+        let first = current.saturating_sub(self.halfmove_clock() as usize);
+        self.boards[first..current].iter().map(|b| b.get_hash()).collect()
+    }
+
+    /// How the game has ended, if it has. Checkmate and stalemate come first;
+    /// threefold repetition and the fifty-move rule are applied as soon as
+    /// they occur rather than waiting for a claim.
+    pub fn outcome(&self) -> Option<Outcome> {
+        let board = self.board();
+        match board.status() {
+            BoardStatus::Checkmate => return Some(Outcome::Checkmate { winner: !board.side_to_move() }),
+            BoardStatus::Stalemate => return Some(Outcome::Stalemate),
+            BoardStatus::Ongoing => {}
+        }
+        let repetitions = self.repetitions();
+        let clock = self.halfmove_clock();
+        let reason = if repetitions >= 5 {
+            DrawReason::FivefoldRepetition
+        } else if clock >= 150 {
+            DrawReason::SeventyFiveMoveRule
+        } else if is_dead_position(&board) {
+            DrawReason::DeadPosition
+        } else if repetitions >= 3 {
+            DrawReason::ThreefoldRepetition
+        } else if clock >= 100 {
+            DrawReason::FiftyMoveRule
+        } else {
+            return None;
+        };
+        Some(Outcome::Draw(reason))
+    }
+
+    /// Pieces taken so far, as `(white pieces lost, black pieces lost)`, in
+    /// the order they were captured.
+    pub fn captured(&self) -> (Vec<Piece>, Vec<Piece>) {
+        let mut captured_white = Vec::new();
+        let mut captured_black = Vec::new();
+
+        for (board, &mv) in self.boards.iter().zip(&self.moves) {
+            if let Some(captured) = captured_piece(board, mv) {
+                if board.side_to_move() == ChessColor::White {
+                    captured_black.push(captured);
+                } else {
+                    captured_white.push(captured);
+                }
+            }
+        }
+
+        (captured_white, captured_black)
+    }
+}
+
+/// The piece `mv` takes on `board`, including a pawn taken en passant.
+pub fn captured_piece(board: &Board, mv: ChessMove) -> Option<Piece> {
+    board.piece_on(mv.get_dest()).or_else(|| {
+        let en_passant = board.piece_on(mv.get_source()) == Some(Piece::Pawn)
+            && mv.get_source().get_file() != mv.get_dest().get_file();
+        en_passant.then_some(Piece::Pawn)
+    })
+}
+
+/// Whether `mv` is a capture or pawn move, which restarts the halfmove clock.
+pub fn resets_clock(board: &Board, mv: ChessMove) -> bool {
+    board.piece_on(mv.get_source()) == Some(Piece::Pawn) || captured_piece(board, mv).is_some()
+}
+
+impl Default for GameRecord {
+    fn default() -> Self {
+        GameRecord::new(Board::default())
+    }
+}
+
+impl<'a> IntoIterator for &'a GameRecord {
+    type Item = &'a ChessMove;
+    type IntoIter = std::slice::Iter<'a, ChessMove>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.moves.iter()
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+
+use chess::{BitBoard, Board, Color as ChessColor, Piece, EMPTY};
+
+/// Why a game ended without a winner, other than stalemate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawReason {
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    /// Neither side has enough material left to mate.
+    DeadPosition,
+    /// A flag fell, but the opponent could not have mated anyway.
+    TimeoutVsInsufficientMaterial,
+}
+
+impl fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DrawReason::ThreefoldRepetition => "threefold repetition",
+            DrawReason::FivefoldRepetition => "fivefold repetition",
+            DrawReason::FiftyMoveRule => "the fifty-move rule",
+            DrawReason::SeventyFiveMoveRule => "the seventy-five-move rule",
+            DrawReason::DeadPosition => "insufficient material",
+            DrawReason::TimeoutVsInsufficientMaterial => "timeout vs insufficient material",
+        })
+    }
+}
+
+/// How a finished game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Checkmate { winner: ChessColor },
+    /// The loser ran out of time.
+    Timeout { winner: ChessColor },
+    Stalemate,
+    Draw(DrawReason),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Checkmate { winner: ChessColor::White } => f.write_str("White wins by checkmate"),
+            Outcome::Checkmate { winner: ChessColor::Black } => f.write_str("Black wins by checkmate"),
+            Outcome::Timeout { winner: ChessColor::White } => f.write_str("White wins on time"),
+            Outcome::Timeout { winner: ChessColor::Black } => f.write_str("Black wins on time"),
+            Outcome::Stalemate => f.write_str("Draw by stalemate"),
+            Outcome::Draw(reason) => write!(f, "Draw by {reason}"),
+        }
+    }
+}
+
+/// True when no sequence of legal moves can lead to mate: bare kings, a
+/// single minor piece, or only bishops that all stand on one square colour.
+pub fn is_dead_position(board: &Board) -> bool {
+    let heavy = *board.pieces(Piece::Pawn) | *board.pieces(Piece::Rook) | *board.pieces(Piece::Queen);
+    if heavy != EMPTY {
+        return false;
+    }
+    let knights = *board.pieces(Piece::Knight);
+    let bishops = *board.pieces(Piece::Bishop);
+    let minors = (knights | bishops).popcnt();
+    if minors <= 1 {
+        return true;
+    }
+    // Bishops confined to one colour cannot mate, however many there are.
+    const LIGHT_SQUARES: BitBoard = BitBoard(0x55AA_55AA_55AA_55AA);
+    knights == EMPTY && (bishops & LIGHT_SQUARES == EMPTY || bishops & !LIGHT_SQUARES == EMPTY)
+}
+
+/// Whether `color` could mate by any series of legal moves, counting help
+/// from the other side. A lone minor piece can when the other king has
+/// anything left to hem it in: K+N mates K+P with the pawn blocking a flight
+/// square.
+pub fn has_mating_material(board: &Board, color: ChessColor) -> bool {
+    let kings = *board.pieces(Piece::King);
+    let own = board.color_combined(color) & !kings;
+    let theirs = board.color_combined(!color) & !kings;
+    if own == EMPTY || is_dead_position(board) {
+        return false;
+    }
+    let minors = *board.pieces(Piece::Knight) | *board.pieces(Piece::Bishop);
+    own & !minors != EMPTY || own.popcnt() >= 2 || theirs != EMPTY
+}
+
+/// The result when `flagged` runs out of time on `board`.
+pub fn timeout_outcome(board: &Board, flagged: ChessColor) -> Outcome {
+    if has_mating_material(board, !flagged) {
+        Outcome::Timeout { winner: !flagged }
+    } else {
+        Outcome::Draw(DrawReason::TimeoutVsInsufficientMaterial)
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt;
+
+use chess::{Board, BoardStatus, ChessMove, Color, File, MoveGen, Piece, Rank, Square};
+
+use crate::engine::record::{captured_piece, GameRecord};
+
+/// Standard Algebraic Notation for the legal move `mv` on `board`, e.g.
+/// `Nbd7`, `exd5`, `O-O`, `e8=Q+` or `Qh4#`.
+pub fn to_san(board: &Board, mv: ChessMove) -> String {
+    let src = mv.get_source();
+    let dest = mv.get_dest();
+    let piece = board.piece_on(src).unwrap_or(Piece::Pawn);
+    let mut san = String::new();
+
+    let file_distance = src.get_file().to_index().abs_diff(dest.get_file().to_index());
+    if piece == Piece::King && file_distance == 2 {
+        san.push_str(if dest.get_file() > src.get_file() { "O-O" } else { "O-O-O" });
+    } else {
+        let capture = captured_piece(board, mv).is_some();
+        if piece == Piece::Pawn {
+            if capture {
+                san.push(file_char(src));
+            }
+        } else {
+            san.push(piece_letter(piece));
+            san.push_str(&disambiguation(board, mv, piece));
+        }
+        if capture {
+            san.push('x');
+        }
+        san.push_str(&dest.to_string());
+        if let Some(promo) = mv.get_promotion() {
+            san.push('=');
+            san.push(piece_letter(promo));
+        }
+    }
+
+    let next = board.make_move_new(mv);
+    if next.checkers().popcnt() > 0 {
+        san.push(if next.status() == BoardStatus::Checkmate { '#' } else { '+' });
+    }
+    san
+}
+
+/// One line of a move list: a move number with White's and Black's moves.
+/// A game that starts with Black to move has no White move on its first
+/// line, and an unfinished pair has no Black move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MovePair {
+    pub number: u32,
+    pub white: Option<String>,
+    pub black: Option<String>,
+}
+
+/// The moves of `record` in SAN, paired up as in `1. e4 e5 2. Nf3`.
+pub fn move_pairs(record: &GameRecord) -> Vec<MovePair> {
+    let mut pairs: Vec<MovePair> = Vec::new();
+    let first = record.start_fullmove_number();
+    for (ply, &mv) in record.iter().enumerate() {
+        let board = record.board_at(ply);
+        let san = Some(to_san(&board, mv));
+        match (board.side_to_move(), pairs.last_mut()) {
+            (Color::Black, Some(pair)) if pair.black.is_none() => pair.black = san,
+            (Color::Black, _) => pairs.push(MovePair { number: first, white: None, black: san }),
+            (Color::White, last) => {
+                let number = last.map_or(first, |pair| pair.number + 1);
+                pairs.push(MovePair { number, white: san, black: None });
+            }
+        }
+    }
+    pairs
+}
+
+/// Why a SAN token could not be matched to a legal move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SanError {
+    /// The text is not SAN at all.
+    Malformed,
+    /// Well-formed, but no legal move fits.
+    Illegal,
+    /// More than one legal move fits.
+    Ambiguous,
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SanError::Malformed => "not a move",
+            SanError::Illegal => "illegal move",
+            SanError::Ambiguous => "ambiguous move",
+        })
+    }
+}
+
+/// Finds the legal move on `board` that `text` describes. Check and mate
+/// suffixes, annotation marks, `0-0` castling and a missing `=` before the
+/// promotion piece are accepted, as is redundant disambiguation.
+pub fn parse_san(board: &Board, text: &str) -> Result<ChessMove, SanError> {
+    let text = text.trim_end_matches(['+', '#', '!', '?']);
+    let text = text.strip_suffix("e.p.").unwrap_or(text);
+
+    if let Some(queenside) = match text {
+        "O-O" | "0-0" => Some(false),
+        "O-O-O" | "0-0-0" => Some(true),
+        _ => None,
+    } {
+        let king = board.king_square(board.side_to_move());
+        return MoveGen::new_legal(board)
+            .find(|mv| {
+                mv.get_source() == king
+                    && mv.get_source().get_file().to_index().abs_diff(mv.get_dest().get_file().to_index()) == 2
+                    && (mv.get_dest().get_file() < king.get_file()) == queenside
+            })
+            .ok_or(SanError::Illegal);
+    }
+
+    let mut chars: Vec<char> = text.chars().collect();
+    let piece = match chars.first().copied().and_then(piece_from_letter) {
+        Some(piece) => {
+            chars.remove(0);
+            piece
+        }
+        None => Piece::Pawn,
+    };
+
+    let mut promotion = None;
+    if let Some(promo) = chars.last().copied().and_then(piece_from_letter) {
+        chars.pop();
+        if chars.last() == Some(&'=') {
+            chars.pop();
+        }
+        promotion = Some(promo);
+    }
+
+    if chars.len() < 2 {
+        return Err(SanError::Malformed);
+    }
+    let dest = square_from(chars[chars.len() - 2], chars[chars.len() - 1]).ok_or(SanError::Malformed)?;
+    let mut from_file = None;
+    let mut from_rank = None;
+    for &c in &chars[..chars.len() - 2] {
+        match c {
+            'a'..='h' => from_file = Some(File::from_index(c as usize - 'a' as usize)),
+            '1'..='8' => from_rank = Some(Rank::from_index(c as usize - '1' as usize)),
+            'x' | ':' | '-' => {}
+            _ => return Err(SanError::Malformed),
+        }
+    }
+
+    let mut candidates = MoveGen::new_legal(board).filter(|mv| {
+        let src = mv.get_source();
+        mv.get_dest() == dest
+            && board.piece_on(src) == Some(piece)
+            && mv.get_promotion() == promotion
+            && from_file.is_none_or(|f| src.get_file() == f)
+            && from_rank.is_none_or(|r| src.get_rank() == r)
+    });
+    match (candidates.next(), candidates.next()) {
+        (Some(mv), None) => Ok(mv),
+        (Some(_), Some(_)) => Err(SanError::Ambiguous),
+        (None, _) => Err(SanError::Illegal),
+    }
+}
+
+fn piece_from_letter(c: char) -> Option<Piece> {
+    match c {
+        'N' => Some(Piece::Knight),
+        'B' => Some(Piece::Bishop),
+        'R' => Some(Piece::Rook),
+        'Q' => Some(Piece::Queen),
+        'K' => Some(Piece::King),
+        _ => None,
+    }
+}
+
+fn square_from(file: char, rank: char) -> Option<Square> {
+    if !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return None;
+    }
+    Some(Square::make_square(
+        Rank::from_index(rank as usize - '1' as usize),
+        File::from_index(file as usize - 'a' as usize),
+    ))
+}
+
+/// Uppercase SAN letter for `piece` (`P` for pawns, which SAN omits).
+pub fn piece_letter(piece: Piece) -> char {
+    match piece {
+        Piece::Pawn => 'P',
+        Piece::Knight => 'N',
+        Piece::Bishop => 'B',
+        Piece::Rook => 'R',
+        Piece::Queen => 'Q',
+        Piece::King => 'K',
+    }
+}
+
+fn file_char(sq: Square) -> char {
+    (b'a' + sq.get_file().to_index() as u8) as char
+}
+
+fn rank_char(sq: Square) -> char {
+    (b'1' + sq.get_rank().to_index() as u8) as char
+}
+
+/// Source file and/or rank needed when another piece of the same kind can
+/// also reach the destination.
+fn disambiguation(board: &Board, mv: ChessMove, piece: Piece) -> String {
+    let src = mv.get_source();
+    let rivals: Vec<Square> = MoveGen::new_legal(board)
+        .filter(|other| {
+            other.get_dest() == mv.get_dest()
+                && other.get_source() != src
+                && board.piece_on(other.get_source()) == Some(piece)
+        })
+        .map(|other| other.get_source())
+        .collect();
+
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|sq| sq.get_file() != src.get_file()) {
+        file_char(src).to_string()
+    } else if rivals.iter().all(|sq| sq.get_rank() != src.get_rank()) {
+        rank_char(src).to_string()
+    } else {
+        src.to_string()
+    }
+}
@@ -0,0 +1,85 @@
+//! Games in progress, kept as PGN with a few extra tags so everything
+//! needed to carry on is in one readable file: the difficulty, which sides
+//! the engine plays, whether it was analysing and the clocks.
+
+use chess::Color as ChessColor;
+use web_time::Duration;
+
+use crate::engine::clock::{ChessClock, TimeControl};
+use crate::engine::pgn::{self, GameResult, PgnTags};
+use crate::engine::{Difficulty, GameRecord};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SavedGame {
+    /// The start position and every move since.
+    pub record: GameRecord,
+    pub difficulty: Difficulty,
+    /// Whether the engine plays each side, indexed by `Color::to_index`.
+    pub engine_plays: [bool; 2],
+    /// Saved from analysis mode, where both sides are moved by hand while
+    /// the engine analyses.
+    pub analysis: bool,
+    pub clock: Option<ChessClock>,
+}
+
+impl SavedGame {
+    pub fn to_pgn(&self) -> String {
+        let name = |color: ChessColor| {
+            if self.engine_plays[color.to_index()] {
+                format!("Chess AI ({})", self.difficulty)
+            } else {
+                "Player".to_string()
+            }
+        };
+        let kind = |color: ChessColor| if self.engine_plays[color.to_index()] { "program" } else { "human" };
+
+        let mut tags = PgnTags::new(name(ChessColor::White), name(ChessColor::Black));
+        tags.result = GameResult::from_outcome(self.record.outcome());
+        tags.extra = vec![
+            ("WhiteType".to_string(), kind(ChessColor::White).to_string()),
+            ("BlackType".to_string(), kind(ChessColor::Black).to_string()),
+            ("Difficulty".to_string(), self.difficulty.to_string()),
+        ];
+        if self.analysis {
+            tags.extra.push(("Mode".to_string(), "analysis".to_string()));
+        }
+        if let Some(clock) = &self.clock {
+            tags.extra.push(("Clock".to_string(), clock.control().to_string()));
+            for (tag, color) in [("WhiteClockMs", ChessColor::White), ("BlackClockMs", ChessColor::Black)] {
+                tags.extra.push((tag.to_string(), clock.remaining(color).as_millis().to_string()));
+            }
+        }
+        pgn::export(&self.record, &tags)
+    }
+
+    /// Reads a game written by `to_pgn`. Plain PGN loads too, as a game
+    /// between two humans at the default difficulty with no clock.
+    pub fn from_pgn(text: &str) -> Result<Self, String> {
+        let game = pgn::parse(text)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .next()
+            .ok_or("no game in save")?;
+
+        let difficulty = match game.tag("Difficulty") {
+            Some(text) => text.parse().map_err(|_| format!("unknown difficulty {text}"))?,
+            None => Difficulty::Medium,
+        };
+        let engine_plays = [game.tag("WhiteType") == Some("program"), game.tag("BlackType") == Some("program")];
+        let analysis = game.tag("Mode") == Some("analysis");
+        let clock = match game.tag("Clock") {
+            Some(text) => {
+                let mut clock = ChessClock::new(text.parse::<TimeControl>()?);
+                for (tag, color) in [("WhiteClockMs", ChessColor::White), ("BlackClockMs", ChessColor::Black)] {
+                    if let Some(ms) = game.tag(tag) {
+                        let ms = ms.parse().map_err(|_| format!("bad {tag} {ms}"))?;
+                        clock.set_remaining(color, Duration::from_millis(ms));
+                    }
+                }
+                Some(clock)
+            }
+            None => None,
+        };
+        Ok(SavedGame { record: game.record, difficulty, engine_plays, analysis, clock })
+    }
+}
@@ -0,0 +1,697 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chess::{Board, BoardStatus, ChessMove, Color as ChessColor, MoveGen, Piece, EMPTY};
+use web_time::{Duration, Instant};
+
+use crate::engine::eval;
+use crate::engine::ordering::{is_tactical, order_captures, MoveOrderer};
+use crate::engine::record::resets_clock;
+use crate::engine::see::see;
+use crate::engine::syzygy::{Tablebase, Wdl};
+use crate::engine::tt::{score_from_tt, score_to_tt, Bound, TranspositionTable, TtStats};
+
+pub const MATE_SCORE: i32 = 1_000_000;
+const INFINITY: i32 = MATE_SCORE + 1;
+/// Scores beyond this are "mate in N" rather than material.
+pub const MATE_BOUND: i32 = MATE_SCORE - 1000;
+/// A win the tablebase proved, less the plies to reach it. Above any
+/// material score but short of the mates.
+pub const TB_WIN_SCORE: i32 = MATE_BOUND - 1;
+/// Scores beyond this are tablebase wins or mates, both counted from the root.
+pub const TB_BOUND: i32 = TB_WIN_SCORE - 1000;
+/// Deepest iteration tried when only a time or node budget is given.
+pub const MAX_SEARCH_DEPTH: i32 = 64;
+
+/// How often (in nodes) the clock is consulted.
+const CHECK_INTERVAL: u64 = 1024;
+
+/// Half-width of the first aspiration window, in centipawns, and the first
+/// iteration to use one: shallower scores swing too much to predict.
+const ASPIRATION_WINDOW: i32 = 50;
+const ASPIRATION_MIN_DEPTH: i32 = 4;
+/// Moves searched at full depth before late move reductions start, and
+/// the shallowest depth they apply at.
+const LMR_FULL_MOVES: usize = 3;
+const LMR_MIN_DEPTH: i32 = 3;
+/// Shallowest depth null-move pruning applies at.
+const NULL_MOVE_MIN_DEPTH: i32 = 3;
+
+/// Budget for one search. Unset fields are unlimited, but at least one of
+/// them should be set or the search runs to `MAX_SEARCH_DEPTH`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchLimits {
+    pub depth: Option<i32>,
+    pub time_ms: Option<u64>,
+    pub nodes: Option<u64>,
+    /// Search every root move with a full window so `root_scores` are exact
+    /// rather than bounds. Needed when callers rank more than the best move.
+    pub exact_root_scores: bool,
+    /// How many best lines to report in `SearchResult::lines`; 0 and 1 both
+    /// mean just the best one. The extra lines get exact scores.
+    pub multi_pv: usize,
+    pub features: SearchFeatures,
+}
+
+/// Selective search techniques, each of which can be switched off to
+/// measure what it is worth. All are on by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchFeatures {
+    /// Principal variation search: after the first move, prove the others
+    /// worse with a null window and only search them fully if that fails.
+    pub pvs: bool,
+    /// Start each iteration with a narrow window around the last score,
+    /// widening it if the score falls outside.
+    pub aspiration: bool,
+    /// Skip a move and cut off if a reduced search still beats beta.
+    /// Never used without pieces, where zugzwang is common.
+    pub null_move: bool,
+    /// Search late quiet moves a ply shallower, re-searching if one of
+    /// them turns out good.
+    pub lmr: bool,
+    /// Search a ply deeper when in check.
+    pub check_extensions: bool,
+}
+
+impl SearchFeatures {
+    pub const ALL: SearchFeatures =
+        SearchFeatures { pvs: true, aspiration: true, null_move: true, lmr: true, check_extensions: true };
+    pub const NONE: SearchFeatures =
+        SearchFeatures { pvs: false, aspiration: false, null_move: false, lmr: false, check_extensions: false };
+}
+
+impl Default for SearchFeatures {
+    fn default() -> Self {
+        SearchFeatures::ALL
+    }
+}
+
+impl SearchLimits {
+    pub fn depth(depth: i32) -> Self {
+        SearchLimits { depth: Some(depth), ..Default::default() }
+    }
+
+    pub fn time_ms(time_ms: u64) -> Self {
+        SearchLimits { time_ms: Some(time_ms), ..Default::default() }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchResult {
+    pub best_move: Option<ChessMove>,
+    /// Score of `best_move` in centipawns from the side to move's point of view.
+    pub score: i32,
+    /// Depth of the last fully completed iteration.
+    pub depth: i32,
+    /// Principal variation, starting with `best_move`.
+    pub pv: Vec<ChessMove>,
+    pub nodes: u64,
+    pub elapsed_ms: u64,
+    /// Root moves with the score they got in the last completed iteration,
+    /// best first.
+    pub root_scores: Vec<(ChessMove, i32)>,
+    /// The best `multi_pv` lines of the last completed iteration, best first.
+    pub lines: Vec<PvLine>,
+    pub tt_stats: TtStats,
+}
+
+/// One line of a MultiPV search: a root move's score and the moves
+/// expected to follow it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PvLine {
+    /// Centipawns from the side to move's point of view.
+    pub score: i32,
+    pub pv: Vec<ChessMove>,
+}
+
+/// Iterative deepening search of `board` within `limits`. Returns the best
+/// move of the last completed iteration together with its score and PV.
+///
+/// `tt` is kept between calls so later searches reuse earlier work.
+pub fn choose_best_move_ab(board: &Board, limits: SearchLimits, tt: &mut TranspositionTable) -> SearchResult {
+    Search::new(board, limits, tt).run(tt, None)
+}
+
+/// Thinking time for one move given the clock: an even share of the
+/// remaining time over `moves_to_go` (or an assumed 30 more moves) plus most
+/// of the increment, never more than half of what is left.
+pub fn time_budget_ms(remaining_ms: u64, increment_ms: u64, moves_to_go: Option<u64>) -> u64 {
+    let share = remaining_ms / moves_to_go.unwrap_or(30).max(1);
+    let budget = share + increment_ms * 3 / 4;
+    budget.min(remaining_ms / 2).max(1)
+}
+
+/// Number of moves until mate for a mate score, negative when being mated.
+pub fn mate_in(score: i32) -> Option<i32> {
+    if score >= MATE_BOUND {
+        Some((MATE_SCORE - score + 1) / 2)
+    } else if score <= -MATE_BOUND {
+        Some(-(MATE_SCORE + score + 1) / 2)
+    } else {
+        None
+    }
+}
+
+/// A legal move at the root with what the search last found for it.
+#[derive(Clone)]
+struct RootMove {
+    mv: ChessMove,
+    score: i32,
+    pv: Vec<ChessMove>,
+}
+
+/// An iterative deepening search that can be advanced a slice at a time.
+///
+/// Work is split at root moves: `step` searches root moves until its slice is
+/// used up and picks up where it left off on the next call. This lets a
+/// single-threaded caller interleave searching with other work.
+pub struct Search {
+    board: Board,
+    /// Earlier positions of the game (see `with_history`) followed by the root.
+    path: Vec<u64>,
+    limits: SearchLimits,
+    start: Instant,
+    nodes: u64,
+    max_depth: i32,
+    root: Vec<RootMove>,
+    // Progress through the iteration currently running.
+    depth: i32,
+    next_root: usize,
+    alpha: i32,
+    /// The aspiration window of the current iteration.
+    window: (i32, i32),
+    best_pv: Vec<ChessMove>,
+    /// Killers and history, kept from one iteration to the next.
+    ordering: MoveOrderer,
+    /// Probed for exact scores below the root.
+    tablebase: Option<Arc<Tablebase>>,
+    /// What the tables make of every root move left, when they cover the root.
+    root_tablebase_score: Option<i32>,
+    /// Plies since the last capture or pawn move at the root.
+    halfmove_clock: u32,
+    result: SearchResult,
+    finished: bool,
+}
+
+impl Search {
+    pub fn new(board: &Board, limits: SearchLimits, tt: &mut TranspositionTable) -> Self {
+        Search::with_history(board, &[], limits, tt)
+    }
+
+    /// Like `new`, but lines that return to one of the `history` positions
+    /// (hashes of the game so far, oldest first) are scored as draws.
+    pub fn with_history(board: &Board, history: &[u64], limits: SearchLimits, tt: &mut TranspositionTable) -> Self {
+        tt.new_search();
+        let hash_move = tt.probe(board.get_hash()).and_then(|e| e.best_move);
+        let root: Vec<RootMove> = MoveOrderer::new()
+            .order(board, MoveGen::new_legal(board).collect(), hash_move, 0)
+            .into_iter()
+            .map(|mv| RootMove { mv, score: 0, pv: vec![mv] })
+            .collect();
+
+        Search {
+            board: *board,
+            path: history.iter().copied().chain([board.get_hash()]).collect(),
+            limits,
+            start: Instant::now(),
+            nodes: 0,
+            max_depth: limits.depth.unwrap_or(MAX_SEARCH_DEPTH).clamp(1, MAX_SEARCH_DEPTH),
+            finished: root.is_empty(),
+            root,
+            depth: 1,
+            next_root: 0,
+            alpha: -INFINITY,
+            window: (-INFINITY, INFINITY),
+            best_pv: Vec::new(),
+            ordering: MoveOrderer::new(),
+            tablebase: None,
+            root_tablebase_score: None,
+            halfmove_clock: 0,
+            result: SearchResult::default(),
+        }
+    }
+
+    /// Uses `tablebase` from the first `step` on, `halfmove_clock` being the
+    /// plies since the last capture or pawn move. When it covers the root,
+    /// only the root moves that keep the best result (see
+    /// `Tablebase::root_moves`) are searched, and they score that result
+    /// unless the search finds a mate. Below the root, positions it covers
+    /// are scored from it rather than searched.
+    pub fn use_tablebase(&mut self, tablebase: Arc<Tablebase>, halfmove_clock: u32) {
+        if let Ok(root) = tablebase.root_moves(&self.board, halfmove_clock) {
+            self.root.retain(|r| root.moves.contains(&r.mv));
+            self.root_tablebase_score = Some(tablebase_score(root.wdl, 1));
+        }
+        self.tablebase = Some(tablebase);
+        self.halfmove_clock = halfmove_clock;
+    }
+
+    /// Searches until done, or until `stop` is raised.
+    pub fn run(mut self, tt: &mut TranspositionTable, stop: Option<&AtomicBool>) -> SearchResult {
+        while !self.step(tt, stop, None) {}
+        self.into_result()
+    }
+
+    /// Advances the search for roughly `slice` (or to the end if `None`).
+    /// Returns `true` once the search has finished.
+    pub fn step(&mut self, tt: &mut TranspositionTable, stop: Option<&AtomicBool>, slice: Option<Duration>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        let slice_start = Instant::now();
+        let mut searcher = Searcher {
+            limits: self.limits,
+            tt,
+            path: std::mem::take(&mut self.path),
+            ordering: std::mem::take(&mut self.ordering),
+            tablebase: self.tablebase.clone(),
+            halfmove_clock: self.halfmove_clock,
+            null_ply: None,
+            stop,
+            start: self.start,
+            nodes: self.nodes,
+            stopped: false,
+        };
+
+        loop {
+            let mv = self.root[self.next_root].mv;
+            let next = self.board.make_move_new(mv);
+            let mut child_pv = Vec::new();
+            let window_alpha = if self.limits.exact_root_scores {
+                -INFINITY
+            } else if self.limits.multi_pv > 1 {
+                // One below, so moves tying with it still get exact scores
+                self.nth_best_so_far(self.limits.multi_pv).saturating_sub(1)
+            } else {
+                self.alpha.max(self.window.0)
+            };
+            let window_beta = self.window.1;
+            searcher.path.push(next.get_hash());
+            searcher.halfmove_clock = if resets_clock(&self.board, mv) { 0 } else { self.halfmove_clock + 1 };
+            let score = -searcher.negamax_ab(&next, self.depth - 1, 1, -window_beta, -window_alpha, &mut child_pv);
+            searcher.halfmove_clock = self.halfmove_clock;
+            searcher.path.pop();
+
+            if searcher.stopped {
+                // An interrupted iteration is only trusted if nothing better exists.
+                if self.result.best_move.is_none() {
+                    let pv = if self.best_pv.is_empty() {
+                        self.alpha = 0; // nothing was searched to the end
+                        vec![mv]
+                    } else {
+                        std::mem::take(&mut self.best_pv)
+                    };
+                    self.record_iteration(pv);
+                }
+                self.finished = true;
+                break;
+            }
+            // Short of a mate, the tables know better than the search
+            let score = match self.root_tablebase_score {
+                Some(tablebase_score) if score.abs() < MATE_BOUND => tablebase_score,
+                _ => score,
+            };
+
+            if score >= window_beta && window_beta < INFINITY {
+                // Failed high: the move is better than the window allows.
+                // Search it first again with the top of the window open.
+                self.window.1 = INFINITY;
+                self.root[..=self.next_root].rotate_right(1);
+                self.restart_iteration();
+                continue;
+            }
+
+            let line: Vec<ChessMove> = std::iter::once(mv).chain(child_pv).collect();
+            if score > self.alpha || self.best_pv.is_empty() {
+                self.alpha = score;
+                self.best_pv = line.clone();
+            }
+            self.root[self.next_root].score = score;
+            self.root[self.next_root].pv = line;
+
+            self.next_root += 1;
+            if self.next_root == self.root.len() && self.alpha <= self.window.0 {
+                // Failed low: every move was worse than the window expected
+                self.window.0 = -INFINITY;
+                self.restart_iteration();
+                continue;
+            }
+            if self.next_root == self.root.len() {
+                // Stable, so the best line stays ahead of equal scores
+                let best = self.best_pv.first().copied();
+                self.root.sort_by_key(|r| (std::cmp::Reverse(r.score), Some(r.mv) != best));
+                let pv = std::mem::take(&mut self.best_pv);
+                searcher.tt.store(self.board.get_hash(), self.depth, self.alpha, Bound::Exact, pv.first().copied());
+                self.record_iteration(pv);
+
+                if self.depth >= self.max_depth || self.alpha.abs() >= MATE_BOUND || searcher.past_soft_limit() {
+                    self.finished = true;
+                    break;
+                }
+                self.depth += 1;
+                self.window = self.aspiration_window();
+                self.restart_iteration();
+            }
+
+            if slice.is_some_and(|slice| slice_start.elapsed() >= slice) {
+                break;
+            }
+        }
+
+        self.nodes = searcher.nodes;
+        self.path = searcher.path;
+        self.ordering = searcher.ordering;
+        self.result.nodes = self.nodes;
+        self.result.elapsed_ms = self.start.elapsed().as_millis() as u64;
+        self.result.tt_stats = searcher.tt.stats();
+        self.finished
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Result of the last completed iteration so far.
+    pub fn result(&self) -> &SearchResult {
+        &self.result
+    }
+
+    pub fn into_result(self) -> SearchResult {
+        self.result
+    }
+
+    fn record_iteration(&mut self, pv: Vec<ChessMove>) {
+        self.result.best_move = pv.first().copied();
+        self.result.score = self.alpha;
+        self.result.depth = self.depth;
+        self.result.root_scores = self.root.iter().map(|r| (r.mv, r.score)).collect();
+        self.result.lines = std::iter::once(PvLine { score: self.alpha, pv: pv.clone() })
+            .chain(
+                self.root
+                    .iter()
+                    .filter(|r| Some(r.mv) != self.result.best_move)
+                    .map(|r| PvLine { score: r.score, pv: r.pv.clone() }),
+            )
+            .take(self.limits.multi_pv.max(1))
+            .collect();
+        self.result.pv = pv;
+    }
+
+    /// Starts the current iteration over, as after widening its window.
+    fn restart_iteration(&mut self) {
+        self.next_root = 0;
+        self.alpha = -INFINITY;
+        self.best_pv.clear();
+    }
+
+    /// A narrow window around the last iteration's score, or an open one
+    /// when aspiration is off or exact scores for several moves are wanted.
+    fn aspiration_window(&self) -> (i32, i32) {
+        let limits = &self.limits;
+        let narrow = limits.features.aspiration && !limits.exact_root_scores && limits.multi_pv <= 1;
+        if !narrow || self.depth < ASPIRATION_MIN_DEPTH || self.result.score.abs() >= TB_BOUND {
+            return (-INFINITY, INFINITY);
+        }
+        (self.result.score - ASPIRATION_WINDOW, self.result.score + ASPIRATION_WINDOW)
+    }
+
+    /// The `n`th best score among the root moves searched so far in this
+    /// iteration: any move that cannot beat it is out of the top `n`.
+    fn nth_best_so_far(&self, n: usize) -> i32 {
+        let mut scores: Vec<i32> = self.root[..self.next_root].iter().map(|r| r.score).collect();
+        if scores.len() < n {
+            return -INFINITY;
+        }
+        scores.sort_unstable_by(|a, b| b.cmp(a));
+        scores[n - 1]
+    }
+}
+
+struct Searcher<'a> {
+    limits: SearchLimits,
+    tt: &'a mut TranspositionTable,
+    /// Hashes from the start of the game history down to the current node.
+    path: Vec<u64>,
+    ordering: MoveOrderer,
+    tablebase: Option<Arc<Tablebase>>,
+    /// Plies since the last capture or pawn move at the current node.
+    halfmove_clock: u32,
+    /// Ply of the node reached by the null move being searched, where
+    /// another null move would just undo it.
+    null_ply: Option<i32>,
+    stop: Option<&'a AtomicBool>,
+    start: Instant,
+    nodes: u64,
+    stopped: bool,
+}
+
+impl Searcher<'_> {
+    // Regular negamax_ab: scores are always from the side to move's point of view
+    fn negamax_ab(
+        &mut self,
+        board: &Board,
+        mut depth: i32,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<ChessMove>,
+    ) -> i32 {
+        pv.clear();
+        if self.tick() {
+            return 0;
+        }
+
+        match board.status() {
+            BoardStatus::Checkmate => return -MATE_SCORE + ply,
+            BoardStatus::Stalemate => return 0,
+            BoardStatus::Ongoing => {}
+        }
+
+        let key = board.get_hash();
+        if self.is_repetition(key) {
+            return 0;
+        }
+        if let Some(score) = self.probe_tablebase(board, ply) {
+            return score;
+        }
+
+        let features = self.limits.features;
+        let in_check = board.checkers().popcnt() > 0;
+        // Also keeps checks out of the quiescence search, which cannot evade them
+        if in_check && features.check_extensions && ply < 2 * MAX_SEARCH_DEPTH {
+            depth += 1;
+        }
+        if depth <= 0 {
+            return self.quiescence_search(board, ply, alpha, beta);
+        }
+
+        let mut hash_move = None;
+        if let Some(entry) = self.tt.probe(key) {
+            hash_move = entry.best_move;
+            if entry.depth >= depth {
+                let score = score_from_tt(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => {
+                        pv.extend(entry.best_move);
+                        return score.clamp(alpha, beta);
+                    }
+                    Bound::Lower if score >= beta => return beta,
+                    Bound::Upper if score <= alpha => return alpha,
+                    _ => {}
+                }
+            }
+        }
+
+        let null_move_ok = features.null_move
+            && !in_check
+            && depth >= NULL_MOVE_MIN_DEPTH
+            && beta.abs() < MATE_BOUND
+            && self.null_ply != Some(ply)
+            && has_pieces(board);
+        if let Some(null) = board.null_move().filter(|_| null_move_ok) {
+            let reduction = if depth > 6 { 3 } else { 2 };
+            let previous = self.null_ply.replace(ply + 1);
+            let clock = self.halfmove_clock;
+            self.halfmove_clock += 1;
+            self.path.push(null.get_hash());
+            let score = -self.negamax_ab(&null, depth - 1 - reduction, ply + 1, -beta, -beta + 1, &mut Vec::new());
+            self.path.pop();
+            self.halfmove_clock = clock;
+            self.null_ply = previous;
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+        }
+
+        let moves = self.ordering.order(board, MoveGen::new_legal(board).collect(), hash_move, ply as usize);
+        let mut child_pv = Vec::new();
+        let mut best_move = None;
+        let original_alpha = alpha;
+        let clock = self.halfmove_clock;
+
+        for (index, mv) in moves.into_iter().enumerate() {
+            let next = board.make_move_new(mv);
+            self.path.push(next.get_hash());
+            self.halfmove_clock = if resets_clock(board, mv) { 0 } else { clock + 1 };
+            let score = if index == 0 {
+                -self.negamax_ab(&next, depth - 1, ply + 1, -beta, -alpha, &mut child_pv)
+            } else {
+                let reduce = features.lmr
+                    && index >= LMR_FULL_MOVES
+                    && depth >= LMR_MIN_DEPTH
+                    && !in_check
+                    && !is_tactical(board, mv)
+                    && next.checkers().popcnt() == 0;
+                let reduction = if reduce { 1 + (depth >= 6 && index >= 2 * LMR_FULL_MOVES) as i32 } else { 0 };
+                // With PVS later moves only have to be shown no better than alpha
+                let child_alpha = if features.pvs { -alpha - 1 } else { -beta };
+                let mut score = -self.negamax_ab(&next, depth - 1 - reduction, ply + 1, child_alpha, -alpha, &mut child_pv);
+                if reduction > 0 && score > alpha {
+                    score = -self.negamax_ab(&next, depth - 1, ply + 1, child_alpha, -alpha, &mut child_pv);
+                }
+                if features.pvs && score > alpha && score < beta {
+                    score = -self.negamax_ab(&next, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+                }
+                score
+            };
+            self.path.pop();
+            self.halfmove_clock = clock;
+            if self.stopped {
+                return 0;
+            }
+
+            if score >= beta {
+                self.ordering.record_cutoff(board, mv, depth, ply as usize);
+                self.tt.store(key, depth, score_to_tt(beta, ply), Bound::Lower, Some(mv));
+                return beta; // Beta cutoff
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = Some(mv);
+                pv.clear();
+                pv.push(mv);
+                pv.extend_from_slice(&child_pv);
+            }
+        }
+
+        let bound = if alpha > original_alpha { Bound::Exact } else { Bound::Upper };
+        self.tt.store(key, depth, score_to_tt(alpha, ply), bound, best_move);
+        alpha
+    }
+
+    // Quiescence search: only explores capture moves/promotions that do not lose material when at depth 0
+    fn quiescence_search(&mut self, board: &Board, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+        if self.tick() {
+            return 0;
+        }
+
+        match board.status() {
+            BoardStatus::Checkmate => return -MATE_SCORE + ply,
+            BoardStatus::Stalemate => return 0,
+            BoardStatus::Ongoing => {}
+        }
+
+        let color = if board.side_to_move() == ChessColor::White { 1 } else { -1 };
+        let stand_pat = color * eval::evaluate(board);
+        if stand_pat >= beta {
+            return beta;
+        }
+        if alpha < stand_pat {
+            alpha = stand_pat;
+        }
+
+        // Captures that lose material by static exchange are not worth a look
+        let captures = order_captures(
+            board,
+            MoveGen::new_legal(board).filter(|&mv| is_tactical(board, mv) && see(board, mv) >= 0).collect(),
+        );
+
+        for mv in captures {
+            let next = board.make_move_new(mv);
+            let score = -self.quiescence_search(&next, ply + 1, -beta, -alpha);
+            if self.stopped {
+                return 0;
+            }
+
+            if score >= beta {
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+            }
+        }
+
+        alpha
+    }
+
+    /// The tablebase score of `board`, if the tables cover it. Only probed
+    /// right after a capture or pawn move: later on, the fifty-move rule may
+    /// already have turned a win the tables report into a draw.
+    fn probe_tablebase(&self, board: &Board, ply: i32) -> Option<i32> {
+        if self.halfmove_clock != 0 {
+            return None;
+        }
+        let wdl = self.tablebase.as_ref()?.probe_wdl(board).ok()?;
+        Some(tablebase_score(wdl, ply))
+    }
+
+    /// Whether the node `key` (the last entry of `path`) repeats an earlier
+    /// position. A single repetition is scored as a draw: if it was worth
+    /// repeating once, it is worth repeating again.
+    fn is_repetition(&self, key: u64) -> bool {
+        let earlier = &self.path[..self.path.len() - 1];
+        // Only positions with the same side to move can match.
+        earlier.iter().rev().skip(1).step_by(2).any(|&h| h == key)
+    }
+
+    /// Counts a node and reports whether the search has to stop.
+    fn tick(&mut self) -> bool {
+        if self.stopped {
+            return true;
+        }
+        self.nodes += 1;
+        if self.limits.nodes.is_some_and(|max| self.nodes >= max) {
+            self.stopped = true;
+        }
+        if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            if self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed)) {
+                self.stopped = true;
+            }
+            if let Some(ms) = self.limits.time_ms {
+                if self.start.elapsed() >= Duration::from_millis(ms) {
+                    self.stopped = true;
+                }
+            }
+        }
+        self.stopped
+    }
+
+    /// Starting another iteration is pointless once half the time is gone,
+    /// since it would almost certainly be cut off.
+    fn past_soft_limit(&self) -> bool {
+        self.limits
+            .time_ms
+            .is_some_and(|ms| self.start.elapsed() >= Duration::from_millis(ms / 2))
+    }
+}
+
+/// Whether the side to move has anything besides pawns and its king. Without
+/// that, zugzwang is common enough that passing is no guide to a position.
+fn has_pieces(board: &Board) -> bool {
+    let pawns_and_king = board.pieces(Piece::Pawn) | board.pieces(Piece::King);
+    board.color_combined(board.side_to_move()) & !pawns_and_king != EMPTY
+}
+
+/// The score of a tablebase result `ply` plies from the root. Cursed wins
+/// and blessed losses are draws under the fifty-move rule.
+fn tablebase_score(wdl: Wdl, ply: i32) -> i32 {
+    match wdl {
+        Wdl::Win => TB_WIN_SCORE - ply,
+        Wdl::Loss => -TB_WIN_SCORE + ply,
+        Wdl::CursedWin | Wdl::Draw | Wdl::BlessedLoss => 0,
+    }
+}
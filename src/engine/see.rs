@@ -0,0 +1,120 @@
+//! Static exchange evaluation: what a capture wins or loses once both
+//! sides have recaptured on the square with their cheapest pieces for as
+//! long as it pays. It looks only at the one square, so pins and
+//! threats elsewhere are ignored, but it is far cheaper than a search.
+
+use chess::{
+    get_bishop_moves, get_king_moves, get_knight_moves, get_pawn_attacks, get_rook_moves, BitBoard, Board, ChessMove,
+    Color as ChessColor, MoveGen, Piece, Square, EMPTY,
+};
+
+/// Exchange values in centipawns, pawn to king. The king's only matters
+/// for being the last piece anyone wants to give up.
+const VALUES: [i32; 6] = [100, 320, 330, 500, 900, 20_000];
+
+/// Material the side to move gains by playing `mv` and then trading
+/// captures on its target square, in centipawns. Negative for captures
+/// that lose material; 0 for quiet moves to a safe square.
+pub fn see(board: &Board, mv: ChessMove) -> i32 {
+    let (from, to) = (mv.get_source(), mv.get_dest());
+    let Some(mut piece) = board.piece_on(from) else { return 0 };
+
+    let mut gains = Vec::with_capacity(32);
+    gains.push(captured_value(board, mv));
+    let mut occupied = *board.combined() ^ BitBoard::from_square(from);
+    if gains[0] > 0 && board.piece_on(to).is_none() {
+        // En passant: the captured pawn leaves from beside the target
+        occupied ^= BitBoard::from_square(Square::make_square(from.get_rank(), to.get_file()));
+    }
+    if let Some(promotion) = mv.get_promotion() {
+        gains[0] += value(promotion) - value(Piece::Pawn);
+        piece = promotion;
+    }
+
+    let mut side = !board.side_to_move();
+    loop {
+        let attackers = attackers_of(board, to, occupied, side);
+        let Some((square, attacker)) = least_valuable(board, attackers) else { break };
+        // The king cannot take into a square the other side still covers
+        let defenders = attackers_of(board, to, occupied ^ BitBoard::from_square(square), !side);
+        if attacker == Piece::King && defenders != EMPTY {
+            break;
+        }
+        // Each entry is what the side making that capture stands to gain
+        gains.push(value(piece) - gains[gains.len() - 1]);
+        occupied ^= BitBoard::from_square(square);
+        piece = attacker;
+        side = !side;
+    }
+
+    // Either side may stop recapturing when carrying on would lose
+    while gains.len() > 1 {
+        let last = gains.pop().unwrap();
+        let previous = gains.last_mut().unwrap();
+        *previous = -(-*previous).max(last);
+    }
+    gains[0]
+}
+
+/// Pieces of `color`, other than the king, that the other side could win
+/// material by taking if it were their move.
+pub fn hanging_pieces(board: &Board, color: ChessColor) -> Vec<Square> {
+    // Hand the move to the other side; in check there is no such board
+    let opponent_to_move = if board.side_to_move() == color { board.null_move() } else { Some(*board) };
+    let Some(board) = opponent_to_move else { return Vec::new() };
+    let targets = board.color_combined(color) & !board.pieces(Piece::King);
+
+    let mut hanging: Vec<Square> = MoveGen::new_legal(&board)
+        .filter(|mv| targets & BitBoard::from_square(mv.get_dest()) != EMPTY && see(&board, *mv) > 0)
+        .map(|mv| mv.get_dest())
+        .collect();
+    hanging.sort_by_key(|square| square.to_index());
+    hanging.dedup();
+    hanging
+}
+
+/// Pieces of `color` still on the `occupied` squares that attack `square`
+/// through them.
+fn attackers_of(board: &Board, square: Square, occupied: BitBoard, color: ChessColor) -> BitBoard {
+    let straight = board.pieces(Piece::Rook) | board.pieces(Piece::Queen);
+    let diagonal = board.pieces(Piece::Bishop) | board.pieces(Piece::Queen);
+    let pawns = |side: ChessColor| {
+        // A pawn of `side` attacks `square` from where an enemy pawn on it would attack
+        get_pawn_attacks(square, !side, !EMPTY) & board.pieces(Piece::Pawn) & board.color_combined(side)
+    };
+    let all = (get_rook_moves(square, occupied) & straight)
+        | (get_bishop_moves(square, occupied) & diagonal)
+        | (get_knight_moves(square) & board.pieces(Piece::Knight))
+        | (get_king_moves(square) & board.pieces(Piece::King))
+        | pawns(ChessColor::White)
+        | pawns(ChessColor::Black);
+    all & occupied & board.color_combined(color)
+}
+
+fn least_valuable(board: &Board, attackers: BitBoard) -> Option<(Square, Piece)> {
+    [Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen, Piece::King]
+        .into_iter()
+        .find_map(|piece| {
+            let square = (attackers & board.pieces(piece)).into_iter().next()?;
+            Some((square, piece))
+        })
+}
+
+/// What `mv` takes, counting en passant.
+fn captured_value(board: &Board, mv: ChessMove) -> i32 {
+    match board.piece_on(mv.get_dest()) {
+        Some(piece) => value(piece),
+        None => {
+            let pawn = board.piece_on(mv.get_source()) == Some(Piece::Pawn);
+            if pawn && mv.get_source().get_file() != mv.get_dest().get_file() {
+                value(Piece::Pawn)
+            } else {
+                0
+            }
+        }
+    }
+}
+
+fn value(piece: Piece) -> i32 {
+    VALUES[piece.to_index()]
+}
@@ -0,0 +1,298 @@
+//! Syzygy endgame tablebases kept in a local directory.
+//!
+//! Tables are read with `shakmaty-syzygy`, which decodes the `.rtbw`
+//! (win/draw/loss) and `.rtbz` (distance to zero) files lazily on first
+//! probe. Positions are handed over through `Setup`, so the rest of the
+//! engine stays on the `chess` crate.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use chess::{Board, BoardStatus, CastleRights, ChessMove, Color as ChessColor, MoveGen, Piece};
+use shakmaty::{CastlingMode, Chess, FromSetup, PositionError, Role, Setup, Square};
+use shakmaty_syzygy::{SyzygyError, Tablebase as Tables, Wdl as TableWdl};
+
+use crate::engine::record::resets_clock;
+
+/// Piece letters in the order Syzygy file names list them.
+const NAME_ORDER: [(Piece, char); 6] = [
+    (Piece::King, 'K'),
+    (Piece::Queen, 'Q'),
+    (Piece::Rook, 'R'),
+    (Piece::Bishop, 'B'),
+    (Piece::Knight, 'N'),
+    (Piece::Pawn, 'P'),
+];
+
+/// Plies without a capture or pawn move after which the game is drawn.
+const FIFTY_MOVE_PLIES: u32 = 100;
+
+/// Win/draw/loss for the side to move. The cursed and blessed results are
+/// wins and losses that the fifty-move rule turns into draws.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl Wdl {
+    /// The same result for the other side.
+    fn flip(self) -> Wdl {
+        match self {
+            Wdl::Loss => Wdl::Win,
+            Wdl::BlessedLoss => Wdl::CursedWin,
+            Wdl::Draw => Wdl::Draw,
+            Wdl::CursedWin => Wdl::BlessedLoss,
+            Wdl::Win => Wdl::Loss,
+        }
+    }
+}
+
+impl From<TableWdl> for Wdl {
+    fn from(wdl: TableWdl) -> Self {
+        match wdl {
+            TableWdl::Loss => Wdl::Loss,
+            TableWdl::BlessedLoss => Wdl::BlessedLoss,
+            TableWdl::Draw => Wdl::Draw,
+            TableWdl::CursedWin => Wdl::CursedWin,
+            TableWdl::Win => Wdl::Win,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProbeError {
+    /// More pieces than the largest table present.
+    TooManyPieces,
+    /// Tables never cover positions where castling is still possible.
+    Castling,
+    /// No table for this material, by its Syzygy name. Probes may need the
+    /// tables for the material left after a capture, too.
+    MissingTable(String),
+    /// A table is there but could not be read.
+    Read(String),
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeError::TooManyPieces => f.write_str("too many pieces for the tables present"),
+            ProbeError::Castling => f.write_str("castling rights remain"),
+            ProbeError::MissingTable(name) => write!(f, "no {name} table"),
+            ProbeError::Read(err) => write!(f, "could not read table: {err}"),
+        }
+    }
+}
+
+impl From<SyzygyError> for ProbeError {
+    fn from(err: SyzygyError) -> Self {
+        match err {
+            SyzygyError::Castling => ProbeError::Castling,
+            SyzygyError::TooManyPieces => ProbeError::TooManyPieces,
+            SyzygyError::MissingTable { material, .. } => ProbeError::MissingTable(material.to_string()),
+            err => ProbeError::Read(err.to_string()),
+        }
+    }
+}
+
+/// The root moves that keep the best result the tables allow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RootMoves {
+    /// That result, for the side to move.
+    pub wdl: Wdl,
+    pub moves: Vec<ChessMove>,
+}
+
+/// The Syzygy tables found in one directory.
+#[derive(Debug)]
+pub struct Tablebase {
+    dir: PathBuf,
+    tables: Tables<Chess>,
+    /// Table files found, both kinds.
+    files: usize,
+}
+
+impl Tablebase {
+    /// Adds the tables in `dir`. Fails if the directory cannot be read or
+    /// holds no tables at all. Files are only opened when first probed.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, String> {
+        let dir = dir.as_ref();
+        let mut tables = new_tables()?;
+        let files = tables.add_directory(dir).map_err(|e| format!("could not read {}: {e}", dir.display()))?;
+        if files == 0 {
+            return Err(format!("no Syzygy tables in {}", dir.display()));
+        }
+        Ok(Tablebase { dir: dir.to_path_buf(), tables, files })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// How many table files, win/draw/loss and distance-to-zero alike, were found.
+    pub fn len(&self) -> usize {
+        self.files
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files == 0
+    }
+
+    /// Pieces, kings included, in the largest table present.
+    pub fn max_pieces(&self) -> usize {
+        self.tables.max_pieces()
+    }
+
+    /// The result with best play for the side to move, taking `board` to
+    /// have just been reached by a capture or pawn move. Needs only the
+    /// win/draw/loss tables.
+    pub fn probe_wdl(&self, board: &Board) -> Result<Wdl, ProbeError> {
+        let pos = self.position(board)?;
+        Ok(self.tables.probe_wdl_after_zeroing(&pos)?.into())
+    }
+
+    /// Plies to the next capture or pawn move (or mate) with best play,
+    /// positive when the side to move wins and negative when it loses. Some
+    /// tables store whole moves, so odd counts may be one too high.
+    pub fn probe_dtz(&self, board: &Board) -> Result<i32, ProbeError> {
+        let pos = self.position(board)?;
+        Ok(self.tables.probe_dtz(&pos)?.ignore_rounding().0)
+    }
+
+    /// The legal moves of `board` that keep the best result the tables
+    /// allow, given `halfmove_clock` plies since the last capture or pawn
+    /// move. Of those, only the ones quickest to a capture or pawn move are
+    /// kept when winning, and the slowest when losing; every drawing move
+    /// is kept. Without distance-to-zero tables, every move keeping the
+    /// result is.
+    pub fn root_moves(&self, board: &Board, halfmove_clock: u32) -> Result<RootMoves, ProbeError> {
+        let wdl = self.probe_wdl(board)?;
+        let moves: Vec<ChessMove> = MoveGen::new_legal(board).collect();
+        let ranks = match moves.iter().map(|&mv| self.dtz_rank(board, mv, halfmove_clock)).collect() {
+            Ok(ranks) => ranks,
+            Err(_) => moves.iter().map(|&mv| self.wdl_rank(board, mv)).collect::<Result<Vec<_>, _>>()?,
+        };
+        let best = ranks.iter().max().copied();
+        Ok(RootMoves {
+            wdl: best.map_or(wdl, |(wdl, _)| wdl),
+            moves: moves.into_iter().zip(ranks).filter(|&(_, rank)| Some(rank) == best).map(|(mv, _)| mv).collect(),
+        })
+    }
+
+    /// How good `mv` is by the distance-to-zero tables: the result it
+    /// keeps, then fewer plies to the next capture or pawn move is better
+    /// for the winner and more is better for the loser.
+    fn dtz_rank(&self, board: &Board, mv: ChessMove, halfmove_clock: u32) -> Result<(Wdl, i32), ProbeError> {
+        let next = board.make_move_new(mv);
+        if next.status() == BoardStatus::Checkmate {
+            return Ok((Wdl::Win, 0));
+        }
+        let zeroing = resets_clock(board, mv);
+        let clock = if zeroing { 0 } else { halfmove_clock + 1 };
+        let dtz = -self.probe_dtz(&next)?;
+        let in_time = clock + dtz.unsigned_abs() <= FIFTY_MOVE_PLIES;
+        let wdl = match dtz.signum() {
+            0 => Wdl::Draw,
+            1 if in_time => Wdl::Win,
+            1 => Wdl::CursedWin,
+            _ if in_time => Wdl::Loss,
+            _ => Wdl::BlessedLoss,
+        };
+        // A capture or pawn move is itself the zeroing move
+        let plies = if zeroing { dtz.signum() } else { dtz };
+        Ok((wdl, -plies))
+    }
+
+    /// How good `mv` is by the win/draw/loss tables alone.
+    fn wdl_rank(&self, board: &Board, mv: ChessMove) -> Result<(Wdl, i32), ProbeError> {
+        Ok((self.probe_wdl(&board.make_move_new(mv))?.flip(), 0))
+    }
+
+    /// `board` as `shakmaty` sees it, or why no table covers it.
+    fn position(&self, board: &Board) -> Result<Chess, ProbeError> {
+        if board.combined().popcnt() as usize > self.max_pieces() {
+            return Err(ProbeError::TooManyPieces);
+        }
+        let no_castling = |color| board.castle_rights(color) == CastleRights::NoRights;
+        if !no_castling(ChessColor::White) || !no_castling(ChessColor::Black) {
+            return Err(ProbeError::Castling);
+        }
+
+        let mut setup = Setup::empty();
+        for square in *board.combined() {
+            let (Some(piece), Some(color)) = (board.piece_on(square), board.color_on(square)) else {
+                continue;
+            };
+            setup.board.set_piece_at(Square::new(square.to_int().into()), role(piece).of(shakmaty_color(color)));
+        }
+        setup.turn = shakmaty_color(board.side_to_move());
+        // `chess` keeps the square of the pawn that may be taken, not the one behind it
+        setup.ep_square = board
+            .en_passant()
+            .and_then(|pawn| pawn.forward(board.side_to_move()))
+            .map(|square| Square::new(square.to_int().into()));
+        Chess::from_setup(setup, CastlingMode::Standard)
+            .or_else(PositionError::ignore_invalid_ep_square)
+            .map_err(|err| ProbeError::Read(err.to_string()))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn new_tables() -> Result<Tables<Chess>, String> {
+    Ok(Tables::new())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn new_tables() -> Result<Tables<Chess>, String> {
+    Err("Syzygy tables can only be read from a local disk".to_string())
+}
+
+fn role(piece: Piece) -> Role {
+    match piece {
+        Piece::Pawn => Role::Pawn,
+        Piece::Knight => Role::Knight,
+        Piece::Bishop => Role::Bishop,
+        Piece::Rook => Role::Rook,
+        Piece::Queen => Role::Queen,
+        Piece::King => Role::King,
+    }
+}
+
+fn shakmaty_color(color: ChessColor) -> shakmaty::Color {
+    match color {
+        ChessColor::White => shakmaty::Color::White,
+        ChessColor::Black => shakmaty::Color::Black,
+    }
+}
+
+/// The Syzygy name for the material on `board`, stronger side first, as in
+/// `KRPvKR`.
+pub fn material_name(board: &Board) -> String {
+    let (white, black) = (side_name(board, ChessColor::White), side_name(board, ChessColor::Black));
+    if stronger(&black, &white) {
+        format!("{black}v{white}")
+    } else {
+        format!("{white}v{black}")
+    }
+}
+
+fn side_name(board: &Board, color: ChessColor) -> String {
+    let mut name = String::new();
+    for (piece, letter) in NAME_ORDER {
+        let count = (board.pieces(piece) & board.color_combined(color)).popcnt();
+        name.extend(std::iter::repeat_n(letter, count as usize));
+    }
+    name
+}
+
+/// Syzygy puts the side with more pieces first, then the one whose pieces
+/// come earlier in `NAME_ORDER`.
+fn stronger(a: &str, b: &str) -> bool {
+    let rank = |name: &str| -> Vec<usize> {
+        name.chars().map(|c| NAME_ORDER.iter().position(|&(_, l)| l == c).unwrap()).collect()
+    };
+    a.len() > b.len() || (a.len() == b.len() && rank(a) < rank(b))
+}
@@ -0,0 +1,154 @@
+use chess::{ChessMove, Color as ChessColor};
+
+use crate::engine::GameRecord;
+
+/// Every line played in a game, with a cursor on the position being looked
+/// at. Stepping back keeps the moves after the cursor so they can be redone,
+/// and playing a different move from an earlier position starts a variation
+/// rather than throwing the old line away.
+#[derive(Clone, Debug)]
+pub struct GameTree {
+    /// `nodes[0]` is the start position.
+    nodes: Vec<Node>,
+    cursor: usize,
+    /// The moves from the start to the cursor.
+    path: GameRecord,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// The move leading here; `None` only for the root.
+    mv: Option<ChessMove>,
+    parent: usize,
+    children: Vec<usize>,
+    /// Index into `children` that `forward` follows: the line last visited.
+    next: usize,
+}
+
+impl GameTree {
+    /// A tree holding the moves of `record` as its main line, with the
+    /// cursor after the last of them.
+    pub fn new(record: GameRecord) -> Self {
+        let mut path = record.clone();
+        path.clear();
+        let root = Node { mv: None, parent: 0, children: Vec::new(), next: 0 };
+        let mut tree = GameTree { nodes: vec![root], cursor: 0, path };
+        for &mv in record.iter() {
+            tree.play(mv);
+        }
+        tree
+    }
+
+    /// The moves from the start to the cursor.
+    pub fn record(&self) -> &GameRecord {
+        &self.path
+    }
+
+    /// Moves from the start to the cursor.
+    pub fn ply(&self) -> usize {
+        self.path.len()
+    }
+
+    /// Plays `mv` from the cursor, following the existing line if it was
+    /// played here before and branching off a new one if not.
+    pub fn play(&mut self, mv: ChessMove) {
+        let node = &self.nodes[self.cursor];
+        let existing = node.children.iter().position(|&child| self.nodes[child].mv == Some(mv));
+        let index = match existing {
+            Some(index) => index,
+            None => {
+                let child = self.nodes.len();
+                self.nodes.push(Node { mv: Some(mv), parent: self.cursor, children: Vec::new(), next: 0 });
+                self.nodes[self.cursor].children.push(child);
+                self.nodes[self.cursor].children.len() - 1
+            }
+        };
+        let node = &mut self.nodes[self.cursor];
+        node.next = index;
+        self.cursor = node.children[index];
+        self.path.push(mv);
+    }
+
+    /// Takes back the move before the cursor, remembering it for `forward`.
+    pub fn back(&mut self) -> Option<ChessMove> {
+        if self.cursor == 0 {
+            return None;
+        }
+        let child = self.cursor;
+        self.cursor = self.nodes[child].parent;
+        let parent = &mut self.nodes[self.cursor];
+        parent.next = parent.children.iter().position(|&c| c == child).unwrap_or(0);
+        self.path.pop()
+    }
+
+    /// Steps back at least one move, and on to the last position where
+    /// `to_move` accepts the side to move. If no earlier position does, the
+    /// cursor stays put and this returns `false`.
+    pub fn take_back(&mut self, to_move: impl Fn(ChessColor) -> bool) -> bool {
+        let ply = self.ply();
+        while self.back().is_some() {
+            if to_move(self.path.board().side_to_move()) {
+                return true;
+            }
+        }
+        self.go_to(ply);
+        false
+    }
+
+    /// Replays the move after the cursor on the line last visited.
+    pub fn forward(&mut self) -> Option<ChessMove> {
+        let mv = self.next_move()?;
+        self.play(mv);
+        Some(mv)
+    }
+
+    /// Moves the cursor to `ply` moves from the start along the current line.
+    pub fn go_to(&mut self, ply: usize) {
+        while self.ply() > ply && self.back().is_some() {}
+        while self.ply() < ply && self.forward().is_some() {}
+    }
+
+    /// Whether nothing has been played from the cursor yet.
+    pub fn at_end(&self) -> bool {
+        self.nodes[self.cursor].children.is_empty()
+    }
+
+    /// The move `forward` would replay.
+    pub fn next_move(&self) -> Option<ChessMove> {
+        let node = &self.nodes[self.cursor];
+        node.children.get(node.next).and_then(|&child| self.nodes[child].mv)
+    }
+
+    /// Every move played from the cursor, oldest line first.
+    pub fn alternatives(&self) -> Vec<ChessMove> {
+        self.nodes[self.cursor].children.iter().filter_map(|&child| self.nodes[child].mv).collect()
+    }
+
+    /// Makes `forward` follow the alternative `steps` away from the current
+    /// one, wrapping around.
+    pub fn cycle_alternative(&mut self, steps: isize) {
+        let node = &mut self.nodes[self.cursor];
+        let count = node.children.len() as isize;
+        if count > 0 {
+            node.next = (node.next as isize + steps).rem_euclid(count) as usize;
+        }
+    }
+
+    /// The moves from the start through the cursor to the end of the
+    /// current line.
+    pub fn line(&self) -> GameRecord {
+        let mut line = self.path.clone();
+        let mut node = &self.nodes[self.cursor];
+        while let Some(&child) = node.children.get(node.next) {
+            node = &self.nodes[child];
+            line.push(node.mv.expect("only the root has no move"));
+        }
+        line
+    }
+}
+
+impl Default for GameTree {
+    fn default() -> Self {
+        GameTree::new(GameRecord::default())
+    }
+}
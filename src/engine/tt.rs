@@ -0,0 +1,188 @@
+use std::fmt;
+use std::mem::size_of;
+
+use chess::ChessMove;
+
+use crate::engine::search::TB_BOUND;
+
+pub const DEFAULT_SIZE_MB: usize = 16;
+
+/// How a stored score relates to the true value of the position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is the exact value.
+    Exact,
+    /// The search failed high; the true value is at least the score.
+    Lower,
+    /// The search failed low; the true value is at most the score.
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtEntry {
+    pub key: u64,
+    pub depth: i32,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<ChessMove>,
+    generation: u8,
+}
+
+/// Counters for the searches since the last `new_search`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TtStats {
+    pub probes: u64,
+    pub hits: u64,
+    pub stores: u64,
+    /// Used slots per thousand.
+    pub fill_permille: u32,
+}
+
+impl TtStats {
+    pub fn hit_rate(&self) -> f64 {
+        if self.probes == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.probes as f64
+        }
+    }
+}
+
+impl fmt::Display for TtStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tt: {} probes, {} hits ({:.1}%), {} stores, {:.1}% full",
+            self.probes,
+            self.hits,
+            self.hit_rate() * 100.0,
+            self.stores,
+            self.fill_permille as f64 / 10.0,
+        )
+    }
+}
+
+/// Fixed-size hash table of search results keyed by `Board::get_hash()`.
+pub struct TranspositionTable {
+    entries: Vec<Option<TtEntry>>,
+    mask: usize,
+    generation: u8,
+    probes: u64,
+    hits: u64,
+    stores: u64,
+}
+
+impl TranspositionTable {
+    /// Allocates a table of at most `size_mb` megabytes (at least one entry).
+    pub fn new(size_mb: usize) -> Self {
+        let bytes = size_mb.max(1) * 1024 * 1024;
+        // Round down to a power of two so the index is a simple mask.
+        let wanted = (bytes / size_of::<Option<TtEntry>>()).max(1);
+        let len = 1usize << (usize::BITS - 1 - wanted.leading_zeros());
+        TranspositionTable {
+            entries: vec![None; len],
+            mask: len - 1,
+            generation: 0,
+            probes: 0,
+            hits: 0,
+            stores: 0,
+        }
+    }
+
+    pub fn resize(&mut self, size_mb: usize) {
+        *self = TranspositionTable::new(size_mb);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|e| *e = None);
+        self.generation = 0;
+        self.probes = 0;
+        self.hits = 0;
+        self.stores = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn size_mb(&self) -> usize {
+        self.entries.len() * size_of::<Option<TtEntry>>() / (1024 * 1024)
+    }
+
+    /// Marks the start of a new search: resets the statistics and ages the
+    /// existing entries so they are replaced first.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.probes = 0;
+        self.hits = 0;
+        self.stores = 0;
+    }
+
+    pub fn probe(&mut self, key: u64) -> Option<TtEntry> {
+        self.probes += 1;
+        let entry = self.entries[key as usize & self.mask].filter(|e| e.key == key);
+        if entry.is_some() {
+            self.hits += 1;
+        }
+        entry
+    }
+
+    pub fn store(&mut self, key: u64, depth: i32, score: i32, bound: Bound, best_move: Option<ChessMove>) {
+        let slot = &mut self.entries[key as usize & self.mask];
+        // Keep a deeper result for the same position from the current search,
+        // otherwise always replace.
+        if let Some(old) = slot {
+            if old.key == key && old.generation == self.generation && old.depth > depth {
+                return;
+            }
+        }
+        let best_move = best_move.or_else(|| slot.filter(|old| old.key == key).and_then(|old| old.best_move));
+        *slot = Some(TtEntry { key, depth, score, bound, best_move, generation: self.generation });
+        self.stores += 1;
+    }
+
+    pub fn stats(&self) -> TtStats {
+        // Sample the start of the table rather than walking all of it.
+        let sample = &self.entries[..self.entries.len().min(1000)];
+        let used = sample.iter().filter(|e| e.is_some_and(|e| e.generation == self.generation)).count();
+        TtStats {
+            probes: self.probes,
+            hits: self.hits,
+            stores: self.stores,
+            fill_permille: (used * 1000 / sample.len()) as u32,
+        }
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        TranspositionTable::new(DEFAULT_SIZE_MB)
+    }
+}
+
+/// Mate and tablebase scores are stored relative to the node rather than the
+/// root, so the same entry is valid wherever in the tree the position is
+/// reached.
+pub fn score_to_tt(score: i32, ply: i32) -> i32 {
+    if score >= TB_BOUND {
+        score + ply
+    } else if score <= -TB_BOUND {
+        score - ply
+    } else {
+        score
+    }
+}
+
+pub fn score_from_tt(score: i32, ply: i32) -> i32 {
+    if score >= TB_BOUND {
+        score - ply
+    } else if score <= -TB_BOUND {
+        score + ply
+    } else {
+        score
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chess::Board;
+
+use crate::engine::record::GameRecord;
+use crate::engine::search::{SearchLimits, SearchResult};
+use crate::engine::Engine;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{self, Receiver, Sender};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+
+#[cfg(target_arch = "wasm32")]
+use crate::engine::search::Search;
+#[cfg(target_arch = "wasm32")]
+use web_time::Duration;
+
+/// How much of each frame the browser build spends searching.
+#[cfg(target_arch = "wasm32")]
+const FRAME_SLICE_MS: u64 = 8;
+
+/// A finished search together with the position it was started from.
+#[derive(Clone, Debug)]
+pub struct SearchOutcome {
+    pub board: Board,
+    pub result: SearchResult,
+}
+
+struct Pending {
+    id: u64,
+    board: Board,
+    stop: Arc<AtomicBool>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct Job {
+    id: u64,
+    record: GameRecord,
+    limits: SearchLimits,
+    stop: Arc<AtomicBool>,
+}
+
+/// Runs engine searches without blocking the caller.
+///
+/// On desktop the search runs on a dedicated thread that owns the engine.
+/// Browsers have no threads, so there the search is advanced a few
+/// milliseconds at a time from `poll`, which the UI calls once per frame.
+pub struct SearchWorker {
+    next_id: u64,
+    pending: Option<Pending>,
+    /// The last completed iteration of the pending search.
+    progress: Option<SearchResult>,
+    #[cfg(not(target_arch = "wasm32"))]
+    jobs: Sender<Job>,
+    /// Search id, result and whether the search has finished.
+    #[cfg(not(target_arch = "wasm32"))]
+    results: Receiver<(u64, SearchResult, bool)>,
+    #[cfg(target_arch = "wasm32")]
+    engine: Engine,
+    #[cfg(target_arch = "wasm32")]
+    search: Option<Search>,
+}
+
+impl SearchWorker {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(tt_size_mb: usize) -> Self {
+        let (jobs, job_rx) = mpsc::channel::<Job>();
+        let (result_tx, results) = mpsc::channel();
+        thread::spawn(move || {
+            let mut engine = Engine::with_hash_mb(tt_size_mb);
+            // Exits once the worker (and with it the job sender) is dropped.
+            for job in job_rx {
+                if job.stop.load(Ordering::Relaxed) {
+                    continue;
+                }
+                engine.set_game(&job.record);
+                let result = engine.search_with(job.limits, Some(&job.stop), |progress| {
+                    let _ = result_tx.send((job.id, progress.clone(), false));
+                });
+                if result_tx.send((job.id, result, true)).is_err() {
+                    break;
+                }
+            }
+        });
+        SearchWorker { next_id: 0, pending: None, progress: None, jobs, results }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(tt_size_mb: usize) -> Self {
+        SearchWorker {
+            next_id: 0,
+            pending: None,
+            progress: None,
+            engine: Engine::with_hash_mb(tt_size_mb),
+            search: None,
+        }
+    }
+
+    /// Starts searching `board`, abandoning any search still running.
+    pub fn start(&mut self, board: &Board, limits: SearchLimits) {
+        self.start_game(&GameRecord::new(*board), limits);
+    }
+
+    /// Starts searching the current position of `record`, scoring returns to
+    /// its earlier positions as draws.
+    pub fn start_game(&mut self, record: &GameRecord, limits: SearchLimits) {
+        self.cancel();
+        let board = &record.board();
+        self.next_id += 1;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let job = Job { id: self.next_id, record: record.clone(), limits, stop: stop.clone() };
+            // The thread only goes away with the worker itself.
+            self.jobs.send(job).expect("search thread has exited");
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.engine.set_game(record);
+            let history = record.reversible_history();
+            self.search = Some(Search::with_history(board, &history, limits, &mut self.engine.tt));
+        }
+
+        self.pending = Some(Pending { id: self.next_id, board: *board, stop });
+    }
+
+    /// Abandons the current search. Its result, if it still arrives, is dropped.
+    pub fn cancel(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            pending.stop.store(true, Ordering::Relaxed);
+        }
+        self.progress = None;
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.search = None;
+        }
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// The deepest finished iteration of the search in progress, as of the
+    /// last `poll`.
+    pub fn progress(&self) -> Option<&SearchResult> {
+        self.progress.as_ref()
+    }
+
+    /// Returns the outcome of the current search once it has finished.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn poll(&mut self) -> Option<SearchOutcome> {
+        while let Ok((id, result, finished)) = self.results.try_recv() {
+            if self.pending.as_ref().is_none_or(|p| p.id != id) {
+                continue;
+            }
+            if !finished {
+                self.progress = Some(result);
+                continue;
+            }
+            let pending = self.pending.take().unwrap();
+            self.progress = None;
+            return Some(SearchOutcome { board: pending.board, result });
+        }
+        None
+    }
+
+    /// Advances the current search by one frame's worth of work and returns
+    /// its outcome once it has finished.
+    #[cfg(target_arch = "wasm32")]
+    pub fn poll(&mut self) -> Option<SearchOutcome> {
+        let search = self.search.as_mut()?;
+        if !search.step(&mut self.engine.tt, None, Some(Duration::from_millis(FRAME_SLICE_MS))) {
+            let depth = self.progress.as_ref().map_or(0, |p| p.depth);
+            if search.result().depth > depth {
+                self.progress = Some(search.result().clone());
+            }
+            return None;
+        }
+        let result = self.search.take().unwrap().into_result();
+        self.progress = None;
+        let pending = self.pending.take()?;
+        Some(SearchOutcome { board: pending.board, result })
+    }
+}
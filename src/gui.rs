@@ -0,0 +1,1986 @@
+﻿use std::collections::HashMap;
+
+use chess::{Board, ChessMove, Color as ChessColor, MoveGen, Piece, Square, BoardStatus, ALL_SQUARES};
+use macroquad::prelude::*;
+use ::rand::{thread_rng, Rng};
+
+use crate::engine::book::OpeningBook;
+use crate::engine::clock::{format_clock, ChessClock, TimeControl};
+use crate::engine::difficulty::{self, Difficulty};
+use crate::engine::fen::{self, parse_fen, FenError};
+use crate::engine::pgn::{self, GameResult, PgnGame, PgnTags};
+use crate::engine::record::{captured_piece, GameRecord};
+use crate::engine::rules::{timeout_outcome, Outcome};
+use crate::engine::san::{self, MovePair};
+use crate::engine::search::{mate_in, SearchLimits, SearchResult};
+use crate::engine::see;
+use crate::engine::savegame::SavedGame;
+use crate::engine::tree::GameTree;
+use crate::engine::tt;
+use crate::engine::worker::SearchWorker;
+use crate::platform;
+use web_time::Duration;
+
+const TILE_SIZE: f32      = 80.0;
+const BOARD_DIM: f32      = TILE_SIZE * 8.0;
+
+/// Spectator move delay, in seconds, and how far +/- change it.
+const DEFAULT_AI_DELAY: f64 = 0.5;
+const AI_DELAY_STEP: f64 = 0.25;
+const MAX_AI_DELAY: f64 = 5.0;
+
+/// Analysis lines shown by default, and at most.
+const DEFAULT_ANALYSIS_LINES: usize = 3;
+const MAX_ANALYSIS_LINES: usize = 5;
+
+/// The opening book the engine plays from, if present. `--book <file>`
+/// picks another.
+const BOOK_FILE: &str = "assets/book.bin";
+
+/// The save slot written after every move, for "Continue last game".
+const AUTOSAVE_SLOT: &str = "autosave";
+
+const PROMO_PIECES: [Piece; 4] = [
+    Piece::Queen,
+    Piece::Rook,
+    Piece::Bishop,
+    Piece::Knight,
+];
+
+pub fn window_conf() -> Conf {
+    Conf {
+        window_title: "Chess AI".to_string(),
+        window_width:  (BOARD_DIM + 200.0) as i32,
+        window_height: BOARD_DIM as i32,
+        ..Default::default()
+    }
+}
+
+pub async fn run_app() {
+    let mut textures = HashMap::new();
+    let assets = [
+        (PieceKey::PawnWhite,   "assets/white-pawn.png"),
+        (PieceKey::KnightWhite, "assets/white-knight.png"),
+        (PieceKey::BishopWhite, "assets/white-bishop.png"),
+        (PieceKey::RookWhite,   "assets/white-rook.png"),
+        (PieceKey::QueenWhite,  "assets/white-queen.png"),
+        (PieceKey::KingWhite,   "assets/white-king.png"),
+        (PieceKey::PawnBlack,   "assets/black-pawn.png"),
+        (PieceKey::KnightBlack, "assets/black-knight.png"),
+        (PieceKey::BishopBlack, "assets/black-bishop.png"),
+        (PieceKey::RookBlack,   "assets/black-rook.png"),
+        (PieceKey::QueenBlack,  "assets/black-queen.png"),
+        (PieceKey::KingBlack,   "assets/black-king.png"),
+    ];
+    for &(key, path) in &assets {
+        let t = load_texture(path).await.unwrap();
+        t.set_filter(FilterMode::Nearest);
+        textures.insert(key, t);
+    }
+
+    let mut state = GameState::Menu;
+    let mut game = ChessGame { 
+        board: Board::default(), 
+        selected_square: None, 
+        dragging: false,
+        premove: None,
+        tree: GameTree::default(),
+        difficulty: Difficulty::Medium,
+        seating: Seating::White,
+        white: Player::Human,
+        black: Player::Engine,
+        ai_delay: DEFAULT_AI_DELAY,
+        analysis_lines: DEFAULT_ANALYSIS_LINES,
+        moved_at: 0.0,
+        board_flipped: false,
+        warn_hanging: true,
+        last_move: None, 
+        captured_white: Vec::new(),
+        captured_black: Vec::new(),
+        notice: None,
+        time_control: None,
+        clock: None,
+        flag: None,
+        move_sources: HashMap::new(),
+    };
+    let mut history = GameRecord::default();
+    let mut worker = SearchWorker::new(tt::DEFAULT_SIZE_MB);
+    let mut replay: Option<Replay> = None;
+    let mut fen_field = TextField::default();
+    let mut time_field = TextField::default();
+    let mut slot_field = TextField::default();
+    let mut saves: Option<SaveList> = None;
+    let mut analysis = Analysis::default();
+    let mut can_continue = platform::read_save(AUTOSAVE_SLOT).is_some();
+    // Closing the window goes through the autosave first
+    prevent_quit();
+
+    let args: Vec<String> = std::env::args().collect();
+    // `--fen "<FEN>"` fills in the menu's start position
+    if let Some(fen) = args.iter().position(|a| a == "--fen").and_then(|i| args.get(i + 1)) {
+        fen_field.text = fen.clone();
+        if let Err(err) = start_position(fen) {
+            println!("Invalid --fen: {err}");
+            game.notice = Some((format!("Invalid FEN: {err}"), get_time()));
+        }
+    }
+    // `--pgn <file>` opens a saved game in the replay viewer
+    if let Some(path) = args.iter().position(|a| a == "--pgn").and_then(|i| args.get(i + 1)) {
+        match std::fs::read_to_string(path) {
+            Ok(text) => replay = load_pgn(&text, &mut game),
+            Err(err) => game.notice = Some((format!("could not read {path}: {err}"), get_time())),
+        }
+        if replay.is_some() {
+            state = GameState::Replay;
+        }
+    }
+    // Without a book the engine simply searches from the first move
+    let book_path = args.iter().position(|a| a == "--book").and_then(|i| args.get(i + 1));
+    let book = match load_file(book_path.map_or(BOOK_FILE, |path| path.as_str())).await {
+        Ok(bytes) => match OpeningBook::from_bytes(&bytes) {
+            Ok(book) => Some(book),
+            Err(err) => {
+                game.notice = Some((format!("Opening book not loaded: {err}"), get_time()));
+                None
+            }
+        },
+        Err(err) => {
+            if let Some(path) = book_path {
+                game.notice = Some((format!("could not read {path}: {err}"), get_time()));
+            }
+            None
+        }
+    };
+    let mut moves_scroll_offset: f32 = 0.0;
+    let mut user_scrolled = false;
+
+
+    loop {
+        clear_background(WHITE);
+        let input = FieldInput::read();
+
+        if is_quit_requested() {
+            quit(&state, &game, &history);
+        }
+
+        // Pausing, undoing and restarting all leave the Playing state, so a
+        // search still running is stale by then.
+        if !matches!(state, GameState::Playing) && worker.is_thinking() {
+            worker.cancel();
+        }
+
+        // A PGN file dropped on the window opens in the replay viewer
+        if let Some(bytes) = get_dropped_files().into_iter().find_map(|file| file.bytes) {
+            if let Some(loaded) = load_pgn(&String::from_utf8_lossy(&bytes), &mut game) {
+                replay = Some(loaded);
+                state = GameState::Replay;
+            }
+        }
+
+        match state {
+            GameState::Menu => {
+                draw_menu();
+                let typing = fen_field.focused || time_field.focused;
+                draw_difficulty_selection(&mut game.difficulty, !typing);
+                draw_seating_selection(&mut game.seating, !typing);
+                if is_key_pressed(KeyCode::T) && !typing {
+                    time_field.text = next_time_preset(&time_field.text);
+                }
+                draw_text_field(
+                    &mut fen_field,
+                    "Start FEN (click to edit, Ctrl+V to paste, empty = standard):",
+                    BOARD_DIM / 2.0 + 75.0,
+                    &input,
+                );
+                draw_text_field(
+                    &mut time_field,
+                    "Time control (T: presets, or type e.g. 3+2, 5 d3, 5 b3; empty = untimed):",
+                    BOARD_DIM / 2.0 + 140.0,
+                    &input,
+                );
+                if is_key_pressed(KeyCode::Enter) {
+                    match (start_position(&fen_field.text), time_control(&time_field.text)) {
+                        (Ok(start), Ok(control)) => {
+                            let record = GameRecord::new(start)
+                                .with_halfmove_clock(fen::halfmove_clock(&fen_field.text))
+                                .with_fullmove_number(fen::fullmove_number(&fen_field.text));
+                            game.time_control = control;
+                            new_game(record, &mut game, &mut history);
+                            fen_field.focused = false;
+                            time_field.focused = false;
+                            game.notice = None;
+                            state = GameState::Playing;
+                        }
+                        (Err(err), _) => game.notice = Some((format!("Invalid FEN: {err}"), get_time())),
+                        (_, Err(err)) => game.notice = Some((format!("Invalid time control: {err}"), get_time())),
+                    }
+                }
+                if is_key_pressed(KeyCode::L) && !typing {
+                    let text = miniquad::window::clipboard_get().unwrap_or_default();
+                    if let Some(loaded) = load_pgn(&text, &mut game) {
+                        replay = Some(loaded);
+                        state = GameState::Replay;
+                    }
+                }
+                if can_continue {
+                    draw_text_centered("C: continue last game", BOARD_DIM / 2.0, BOARD_DIM / 2.0 - 10.0, 20.0);
+                    if is_key_pressed(KeyCode::C) && !typing && load_slot(AUTOSAVE_SLOT, &mut game, &mut history) {
+                        state = GameState::Playing;
+                    }
+                }
+                if is_key_pressed(KeyCode::G) && !typing {
+                    saves = Some(SaveList { slots: platform::list_saves(), selected: 0 });
+                    state = GameState::Saves;
+                }
+                draw_notice(&mut game.notice, BOARD_DIM / 2.0 - 90.0, BOARD_DIM - 40.0);
+            }
+
+            GameState::Saves => {
+                let Some(list) = saves.as_mut() else {
+                    state = GameState::Menu;
+                    continue;
+                };
+                let clicked = draw_save_list(list);
+                if is_key_pressed(KeyCode::Down) && list.selected + 1 < list.slots.len() {
+                    list.selected += 1;
+                }
+                if is_key_pressed(KeyCode::Up) {
+                    list.selected = list.selected.saturating_sub(1);
+                }
+                if is_key_pressed(KeyCode::Delete) && list.selected < list.slots.len() {
+                    platform::delete_save(&list.slots.remove(list.selected));
+                    list.selected = list.selected.min(list.slots.len().saturating_sub(1));
+                    can_continue = platform::read_save(AUTOSAVE_SLOT).is_some();
+                }
+                if clicked || is_key_pressed(KeyCode::Enter) {
+                    if let Some(slot) = list.slots.get(list.selected) {
+                        if load_slot(slot, &mut game, &mut history) {
+                            state = GameState::Playing;
+                        }
+                    }
+                }
+                if is_key_pressed(KeyCode::Escape) {
+                    state = GameState::Menu;
+                }
+                draw_notice(&mut game.notice, BOARD_DIM / 2.0 - 90.0, BOARD_DIM - 40.0);
+            }
+
+            GameState::SaveAs => {
+                draw_board(game.orientation());
+                draw_pieces(&game.board, &textures, game.orientation(), None);
+                draw_rectangle(0.0, 0.0, BOARD_DIM + 200.0, BOARD_DIM, BLACK.with_alpha(0.5));
+                draw_rectangle(30.0, BOARD_DIM / 2.0 - 40.0, BOARD_DIM + 140.0, 90.0, WHITE);
+                draw_text_field(
+                    &mut slot_field,
+                    "Save as (Enter: save, Esc: cancel):",
+                    BOARD_DIM / 2.0,
+                    &input,
+                );
+                slot_field.focused = true;
+                if is_key_pressed(KeyCode::Enter) && !slot_field.text.trim().is_empty() {
+                    let slot = slot_field.text.trim();
+                    let message = match platform::write_save(slot, &saved_game(&game, &history).to_pgn()) {
+                        Ok(()) => format!("Saved as {slot}"),
+                        Err(err) => err,
+                    };
+                    game.notice = Some((message, get_time()));
+                    state = GameState::Paused;
+                }
+                if is_key_pressed(KeyCode::Escape) {
+                    state = GameState::Paused;
+                }
+            }
+
+            GameState::Replay => {
+                let Some(view) = replay.as_mut() else {
+                    state = GameState::Menu;
+                    continue;
+                };
+                let record = &view.games[view.game].record;
+                if is_key_pressed(KeyCode::F) {
+                    view.orientation = view.orientation.flip();
+                }
+                draw_board(view.orientation);
+                draw_pieces(&record.board_at(view.ply), &textures, view.orientation, None);
+                draw_last_move(view.ply.checked_sub(1).map(|i| record.moves()[i]), view.orientation);
+                draw_replay_panel(view);
+
+                if is_key_pressed(KeyCode::Right) {
+                    view.ply = (view.ply + 1).min(record.len());
+                }
+                if is_key_pressed(KeyCode::Left) {
+                    view.ply = view.ply.saturating_sub(1);
+                }
+                if is_key_pressed(KeyCode::Home) {
+                    view.ply = 0;
+                }
+                if is_key_pressed(KeyCode::End) {
+                    view.ply = record.len();
+                }
+                if is_key_pressed(KeyCode::Down) && view.game + 1 < view.games.len() {
+                    view.game += 1;
+                    view.ply = 0;
+                }
+                if is_key_pressed(KeyCode::Up) && view.game > 0 {
+                    view.game -= 1;
+                    view.ply = 0;
+                }
+                if is_key_pressed(KeyCode::T) {
+                    // Take over: play on against the engine from the shown
+                    // position, as the side to move
+                    let mut record = view.games[view.game].record.clone();
+                    record.truncate(view.ply);
+                    game.tree = GameTree::new(record);
+                    show_cursor(&mut game, &mut history);
+                    game.seating = match game.board.side_to_move() {
+                        ChessColor::White => Seating::White,
+                        ChessColor::Black => Seating::Black,
+                    };
+                    (game.white, game.black) = game.seating.players();
+                    game.board_flipped = false;
+                    game.move_sources.clear();
+                    game.clock = game.time_control.map(ChessClock::new);
+                    game.flag = None;
+                    state = GameState::Playing;
+                }
+                if is_key_pressed(KeyCode::Escape) {
+                    replay = None;
+                    state = GameState::Menu;
+                }
+            }
+
+        GameState::Playing => {
+            if run_clock(&mut game) {
+                state = GameState::GameOver;
+            }
+            if is_key_pressed(KeyCode::F) {
+                game.board_flipped = !game.board_flipped;
+            }
+            if is_key_pressed(KeyCode::W) {
+                game.warn_hanging = !game.warn_hanging;
+                let state = if game.warn_hanging { "on" } else { "off" };
+                game.notice = Some((format!("Hanging piece warnings {state} (W)"), get_time()));
+            }
+            // Arrow keys walk the move tree; Up/Down pick the variation
+            // that Right follows where more than one was played
+            let ply = game.tree.ply();
+            if is_key_pressed(KeyCode::Left) {
+                game.tree.back();
+            } else if is_key_pressed(KeyCode::Right) {
+                game.tree.forward();
+            } else if is_key_pressed(KeyCode::Home) {
+                game.tree.go_to(0);
+            } else if is_key_pressed(KeyCode::End) {
+                game.tree.go_to(usize::MAX);
+            }
+            if game.tree.ply() != ply {
+                // Whatever the engine was searching is no longer on the board
+                worker.cancel();
+                show_cursor(&mut game, &mut history);
+            }
+            if is_key_pressed(KeyCode::Up) {
+                game.tree.cycle_alternative(-1);
+            }
+            if is_key_pressed(KeyCode::Down) {
+                game.tree.cycle_alternative(1);
+            }
+
+            let orientation = game.orientation();
+            draw_board(orientation);
+            let lifted = game.selected_square.filter(|_| game.dragging);
+            draw_pieces(&game.board, &textures, orientation, lifted);
+            highlight_selection(game.selected_square, orientation);
+            if let Some(sq) = game.selected_square {
+                draw_legal_moves(sq, &game.board, orientation);
+            }
+            draw_game_status(&game.board);
+            draw_last_move(game.last_move, orientation);
+            draw_premove(game.premove, orientation);
+            let to_move = game.board.side_to_move();
+            if game.warn_hanging && game.player(to_move) == Player::Human {
+                draw_hanging(&see::hanging_pieces(&game.board, to_move), orientation);
+            }
+            if let Some(sq) = lifted {
+                draw_dragged_piece(&game.board, sq, &textures);
+            }
+            if !game.tree.at_end() {
+                draw_text_centered(
+                    "Earlier position: move to branch off, End to return",
+                    BOARD_DIM / 2.0,
+                    BOARD_DIM - 8.0,
+                    20.0,
+                );
+            }
+            draw_captured_pieces(&game.captured_white, &game.captured_black, &textures);
+
+            // Panel base
+            let panel_x = BOARD_DIM + 10.0;
+            let (pw, ph) = (40.0, 40.0);
+
+            // Pause Button
+            draw_rectangle(panel_x, 10.0, pw, ph, LIGHTGRAY);
+            let (bw, bh) = (pw * 0.2, ph * 0.7);
+            let by = 10.0 + (ph - bh) / 2.0;
+            draw_rectangle(panel_x + pw * 0.2, by, bw, bh, BLACK);
+            draw_rectangle(panel_x + pw * 0.6, by, bw, bh, BLACK);
+
+            if is_mouse_button_pressed(MouseButton::Left) {
+                let (mx, my) = mouse_position();
+                if mx >= panel_x && mx <= panel_x + pw && my >= 10.0 && my <= 10.0 + ph {
+                    state = GameState::Paused;
+                }
+            }
+            // Right-click drops whatever is in hand, queued premove included
+            if is_mouse_button_pressed(MouseButton::Right) {
+                game.selected_square = None;
+                game.dragging = false;
+                game.premove = None;
+            }
+            let to_move = game.board.side_to_move();
+            if game.player(to_move) == Player::Human {
+                if let Some((from, to)) = handle_mouse(&mut game, to_move) {
+                    if needs_promotion(&game.board, from, to) {
+                        if game.board.legal(ChessMove::new(from, to, Some(Piece::Queen))) {
+                            state = GameState::Promotion { from, to };
+                        }
+                    } else {
+                        let mv = ChessMove::new(from, to, None);
+                        if game.board.legal(mv) {
+                            play_move(&mut game, &mut history, mv);
+                        }
+                    }
+                }
+            } else if game.player(!to_move) == Player::Human {
+                // Queue a move for when the engine has replied; it is only
+                // checked for legality then, so promotions become queens
+                if let Some((from, to)) = handle_mouse(&mut game, !to_move) {
+                    let promotion = needs_promotion(&game.board, from, to).then_some(Piece::Queen);
+                    game.premove = Some(ChessMove::new(from, to, promotion));
+                }
+            }
+
+            if is_key_pressed(KeyCode::P) || is_key_pressed(KeyCode::Escape) {
+                state = GameState::Paused;
+            }
+            if is_key_pressed(KeyCode::S) {
+                save_pgn(&mut game, &history);
+            }
+
+            if game.white == Player::Engine && game.black == Player::Engine {
+                if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+                    game.ai_delay = (game.ai_delay + AI_DELAY_STEP).min(MAX_AI_DELAY);
+                }
+                if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+                    game.ai_delay = (game.ai_delay - AI_DELAY_STEP).max(0.0);
+                }
+                draw_text(
+                    &format!("Move delay {:.2}s (+/-)", game.ai_delay),
+                    BOARD_DIM + 10.0,
+                    BOARD_DIM - 10.0,
+                    18.0,
+                    DARKGRAY,
+                );
+            }
+
+            if game.player(game.board.side_to_move()) == Player::Engine {
+                if game.outcome(&history).is_some() {
+                    state = GameState::GameOver;
+                } else if !worker.is_thinking() && game.engine_may_move() && game.tree.at_end() {
+                    // Off the end of the line the engine waits rather than
+                    // overwriting moves that could be replayed
+                    let profile = game.difficulty.profile();
+                    match book.as_ref().and_then(|book| profile.book_move(book, &history, &mut thread_rng())) {
+                        Some(mv) => play_engine_move(&mut game, &mut history, mv, MoveSource::Book),
+                        None => worker.start_game(&history, game.engine_limits()),
+                    }
+                }
+            }
+
+            if game.analysing() {
+                if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+                    game.analysis_lines = (game.analysis_lines + 1).min(MAX_ANALYSIS_LINES);
+                }
+                if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+                    game.analysis_lines = (game.analysis_lines - 1).max(1);
+                }
+                // Search the shown position until it changes, without limits;
+                // a search cut short by pausing starts over
+                let lines = game.analysis_lines;
+                let stale = analysis.board != Some(game.board) || analysis.lines != lines;
+                if stale || (!worker.is_thinking() && !analysis.finished) {
+                    worker.start_game(&history, SearchLimits { multi_pv: lines, ..Default::default() });
+                    analysis = Analysis { board: Some(game.board), lines, result: None, finished: false };
+                }
+            }
+
+            if let Some(outcome) = worker.poll() {
+                if game.analysing() {
+                    if analysis.board == Some(outcome.board) {
+                        analysis.result = Some(outcome.result);
+                        analysis.finished = true;
+                    }
+                } else if outcome.board == game.board && game.flag.is_none() {
+                    // Only play the move if the position is still the one searched
+                    let result = outcome.result;
+                    let profile = game.difficulty.profile();
+                    if let Some(best_mv) = difficulty::pick_move(&result.root_scores, &profile, &mut thread_rng()) {
+                        play_engine_move(&mut game, &mut history, best_mv, MoveSource::Search);
+                    }
+                }
+            }
+
+            if game.analysing() {
+                let depth = analysis.result.as_ref().map_or(0, |r| r.depth);
+                if let Some(progress) = worker.progress().filter(|p| p.depth > depth) {
+                    analysis.result = Some(progress.clone());
+                }
+                if let Some(result) = &analysis.result {
+                    draw_analysis_arrows(result, &game.board, orientation);
+                    draw_eval_bar(white_score(result.score, &game.board), orientation);
+                }
+            } else if worker.is_thinking() {
+                draw_thinking_indicator();
+            }
+
+            // ------ Pause Button ------
+
+            let pause_button_x = BOARD_DIM + 10.0;
+            let pause_button_y = 10.0;
+            let pause_button_width = 40.0;
+            let pause_button_height = 40.0;
+
+            // Background under pause button
+            draw_rectangle(pause_button_x, pause_button_y, pause_button_width, pause_button_height, LIGHTGRAY);
+
+            // Pause button "bars"
+            let bar_width = pause_button_width * 0.2;
+            let bar_height = pause_button_height * 0.7;
+            let bar_y = pause_button_y + (pause_button_height - bar_height) / 2.0;
+            draw_rectangle(pause_button_x + pause_button_width * 0.2, bar_y, bar_width, bar_height, BLACK);
+            draw_rectangle(pause_button_x + pause_button_width * 0.6, bar_y, bar_width, bar_height, BLACK);
+
+            // Pause Button click
+            if is_mouse_button_pressed(MouseButton::Left) {
+                let (mx, my) = mouse_position();
+                if mx >= pause_button_x && mx <= pause_button_x + pause_button_width &&
+                   my >= pause_button_y && my <= pause_button_y + pause_button_height {
+                    state = GameState::Paused;
+                }
+            }
+
+
+            // ------ Moves Panel ------
+
+            let panel_x = BOARD_DIM + 10.0;
+            let panel_width = 180.0;
+
+            // Start moves label **after** pause button
+            let moves_label_y = pause_button_y + pause_button_height + 10.0;
+            draw_text("Moves:", panel_x, moves_label_y, 24.0, BLACK);
+
+            // Moves Area
+            let moves_area_top = moves_label_y + 30.0;
+            let moves_area_height = BOARD_DIM * 0.45;
+            let moves_area_bottom = moves_area_top + moves_area_height;
+
+            // Draw background
+            draw_rectangle(panel_x, moves_area_top, panel_width, moves_area_height, LIGHTGRAY);
+
+            // Scrolling logic
+            let move_line_height = 22.0;
+            let line = game.tree.line();
+            let pairs = san::move_pairs(&line);
+            let sources = move_sources(&game, &line);
+            let total_moves_height = pairs.len() as f32 * move_line_height + 8.0;
+            let max_scroll = (total_moves_height - moves_area_height).max(0.0);
+
+            let (_, scroll_y) = mouse_wheel();
+            moves_scroll_offset -= scroll_y * 20.0;
+            moves_scroll_offset = moves_scroll_offset.clamp(-max_scroll, 0.0);
+
+            if !user_scrolled && total_moves_height > moves_area_height {
+                moves_scroll_offset = -max_scroll;
+            }
+            if scroll_y.abs() > 0.0 {
+                user_scrolled = true;
+            }
+            if (moves_scroll_offset + max_scroll).abs() < 5.0 {
+                user_scrolled = false;
+            }
+
+            // --- Dragging Scrollbar ---
+            let mouse = mouse_position();
+            let scrollbar_width = 6.0;
+            let scrollbar_x = panel_x + panel_width - scrollbar_width;
+            let scrollbar_height = moves_area_height * (moves_area_height / total_moves_height).min(moves_area_height);
+            let scrollbar_max_offset = moves_area_height - scrollbar_height;
+            let scrollbar_y = moves_area_top + (-moves_scroll_offset / max_scroll * scrollbar_max_offset);
+
+            static mut DRAGGING_SCROLL: bool = false;
+            static mut DRAG_OFFSET_Y: f32 = 0.0;
+
+            if is_mouse_button_pressed(MouseButton::Left) &&
+               mouse.0 >= scrollbar_x && mouse.0 <= scrollbar_x + scrollbar_width &&
+               mouse.1 >= scrollbar_y && mouse.1 <= scrollbar_y + scrollbar_height {
+                unsafe {
+                    DRAGGING_SCROLL = true;
+                    DRAG_OFFSET_Y = mouse.1 - scrollbar_y;
+                }
+            }
+            if is_mouse_button_down(MouseButton::Left) {
+                unsafe {
+                    if DRAGGING_SCROLL {
+                        let mut new_scrollbar_y = mouse.1 - DRAG_OFFSET_Y;
+                        new_scrollbar_y = new_scrollbar_y.clamp(moves_area_top, moves_area_bottom - scrollbar_height);
+                        moves_scroll_offset = -(new_scrollbar_y - moves_area_top) / scrollbar_max_offset * max_scroll;
+                    }
+                }
+            } else {
+                unsafe { DRAGGING_SCROLL = false; }
+            }
+
+            // Draw each move; clicking one shows the position after it
+            let black_first = history.start().side_to_move() == ChessColor::Black;
+            let rows = (moves_area_top, moves_area_bottom, move_line_height, moves_scroll_offset);
+            let clicked = draw_move_list(&pairs, &sources, black_first, game.tree.ply(), panel_x, panel_width, rows);
+            if let Some(ply) = clicked.filter(|&ply| ply != game.tree.ply()) {
+                worker.cancel();
+                game.tree.go_to(ply);
+                show_cursor(&mut game, &mut history);
+            }
+
+            // Draw scrollbar
+            if max_scroll > 0.0 {
+                let hovered = mouse.0 >= scrollbar_x && mouse.0 <= scrollbar_x + scrollbar_width &&
+                              mouse.1 >= moves_area_top && mouse.1 <= moves_area_bottom;
+                draw_rectangle(
+                    scrollbar_x,
+                    moves_area_top + (-moves_scroll_offset / max_scroll) * scrollbar_max_offset,
+                    scrollbar_width,
+                    scrollbar_height,
+                    if hovered { GRAY } else { DARKGRAY },
+                );
+            }
+
+            // ------ End Moves Panel ------
+
+            let mut notice_y = moves_area_bottom + 24.0;
+            if sources.iter().any(Option::is_some) {
+                // Key to the colours of the engine's moves in the list
+                draw_text("Engine:", panel_x, moves_area_bottom + 16.0, 16.0, DARKGRAY);
+                draw_text("book", panel_x + 50.0, moves_area_bottom + 16.0, 16.0, MoveSource::Book.color());
+                draw_text("search", panel_x + 88.0, moves_area_bottom + 16.0, 16.0, MoveSource::Search.color());
+                notice_y += 16.0;
+            }
+            if let Some(clock) = &game.clock {
+                let clocks_y = notice_y - 14.0;
+                draw_clocks(clock, game.live_board().side_to_move(), orientation, panel_x, clocks_y);
+                notice_y += 70.0;
+            }
+            if let (true, Some(result)) = (game.analysing(), &analysis.result) {
+                notice_y = draw_analysis_panel(result, &game.board, game.analysis_lines, panel_x, notice_y);
+            }
+            let alternatives = game.tree.alternatives();
+            if alternatives.len() > 1 {
+                let next = game.tree.next_move();
+                let names: Vec<String> = alternatives
+                    .iter()
+                    .map(|&mv| {
+                        let san = san::to_san(&game.board, mv);
+                        if Some(mv) == next { format!("[{san}]") } else { san }
+                    })
+                    .collect();
+                draw_text("Variations (Up/Down):", panel_x, notice_y, 16.0, DARKGRAY);
+                draw_wrapped(&names.join(" "), panel_x, notice_y + 18.0);
+                notice_y += 60.0;
+            }
+            draw_notice(&mut game.notice, panel_x, notice_y);
+
+            if game.outcome(&history).is_some() {
+                state = GameState::GameOver;
+            }
+        }
+
+
+            GameState::Promotion { from, to } => {
+                if run_clock(&mut game) {
+                    state = GameState::GameOver;
+                    continue;
+                }
+                draw_board(game.orientation());
+                draw_pieces(&game.board, &textures, game.orientation(), None);
+                draw_promotion_ui(from, to, &textures, &mut state, &mut game, &mut history);
+            }
+
+            GameState::Paused => {
+                draw_board(game.orientation());
+                draw_pieces(&game.board, &textures, game.orientation(), None);
+                draw_pause_menu(&mut state, &mut game, &mut history);
+                if matches!(state, GameState::SaveAs) {
+                    slot_field = TextField { text: format!("Game {}", pgn::today()), focused: true };
+                }
+                draw_notice(&mut game.notice, BOARD_DIM + 10.0, 36.0);
+            }
+
+            GameState::GameOver => {
+                draw_board(game.orientation());
+                draw_pieces(&game.board, &textures, game.orientation(), None);
+                draw_game_over_ui(&mut state, &mut game, &mut history);
+                if is_key_pressed(KeyCode::S) {
+                    save_pgn(&mut game, &history);
+                }
+                draw_notice(&mut game.notice, BOARD_DIM + 10.0, 36.0);
+            }
+        }
+
+        next_frame().await;
+    }
+}
+
+enum GameState {
+    Menu,
+    Replay,
+    Playing,
+    Paused,
+    Promotion { from: Square, to: Square },
+    GameOver,
+    /// Picking a saved game to load.
+    Saves,
+    /// Naming a save slot for the game in progress.
+    SaveAs,
+}
+
+#[derive(Clone,Copy,PartialEq,Eq,Hash)]
+enum PieceKey {
+    PawnWhite, KnightWhite, BishopWhite, RookWhite, QueenWhite, KingWhite,
+    PawnBlack, KnightBlack, BishopBlack, RookBlack, QueenBlack, KingBlack,
+}
+
+/// Games loaded from a PGN file and the position being shown.
+struct Replay {
+    games: Vec<PgnGame>,
+    game: usize,
+    ply: usize,
+    orientation: Orientation,
+}
+
+/// Live engine analysis of the position on the board.
+#[derive(Default)]
+struct Analysis {
+    /// The position being searched and for how many lines.
+    board: Option<Board>,
+    lines: usize,
+    /// Deepest result so far.
+    result: Option<SearchResult>,
+    finished: bool,
+}
+
+/// The saved games offered on the load screen.
+struct SaveList {
+    slots: Vec<String>,
+    selected: usize,
+}
+
+/// Who moves for one side.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Player {
+    Human,
+    Engine,
+}
+
+/// The menu's choice of who plays which side.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Seating {
+    White,
+    Black,
+    Random,
+    AiVsAi,
+    Hotseat,
+    /// Both sides moved by hand while the engine analyses.
+    Analysis,
+}
+
+impl Seating {
+    /// `(white, black)` for a new game.
+    fn players(self) -> (Player, Player) {
+        match self {
+            Seating::White => (Player::Human, Player::Engine),
+            Seating::Black => (Player::Engine, Player::Human),
+            Seating::Random if thread_rng().gen_bool(0.5) => Seating::White.players(),
+            Seating::Random => Seating::Black.players(),
+            Seating::AiVsAi => (Player::Engine, Player::Engine),
+            Seating::Hotseat | Seating::Analysis => (Player::Human, Player::Human),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Seating::White => "Play White",
+            Seating::Black => "Play Black",
+            Seating::Random => "Random side",
+            Seating::AiVsAi => "AI vs AI",
+            Seating::Hotseat => "Hotseat (two players)",
+            Seating::Analysis => "Analysis board",
+        }
+    }
+}
+
+struct ChessGame {
+    board: Board,
+    selected_square: Option<Square>,
+    dragging: bool,  // the selected piece follows the mouse
+    premove: Option<ChessMove>,  // queued while the engine thinks
+    tree: GameTree,  // every line played, with the shown position as cursor
+    difficulty: Difficulty,
+    seating: Seating,
+    white: Player,
+    black: Player,
+    ai_delay: f64,    // seconds between moves when the engine plays itself
+    analysis_lines: usize,  // MultiPV count in analysis mode
+    moved_at: f64,    // get_time() of the last move
+    board_flipped: bool,  // turned around from the automatic orientation
+    warn_hanging: bool,  // mark the human's pieces the opponent could win
+    last_move: Option<ChessMove>,         
+    captured_white: Vec<Piece>,
+    captured_black: Vec<Piece>,         
+    notice: Option<(String, f64)>,  // message and the time it was posted
+    time_control: Option<TimeControl>,  // None for untimed games
+    clock: Option<ChessClock>,
+    flag: Option<Outcome>,  // how the game ended if a flag fell
+    move_sources: HashMap<(u64, ChessMove), MoveSource>,  // how the engine chose its moves, by position hash
+}
+
+/// Where one of the engine's moves came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MoveSource {
+    Book,
+    Search,
+}
+
+impl MoveSource {
+    /// Its colour in the move list.
+    fn color(self) -> Color {
+        match self {
+            MoveSource::Book => DARKGREEN,
+            MoveSource::Search => DARKBLUE,
+        }
+    }
+}
+
+
+impl ChessGame {
+    fn player(&self, color: ChessColor) -> Player {
+        match color {
+            ChessColor::White => self.white,
+            ChessColor::Black => self.black,
+        }
+    }
+
+    /// The human's side at the bottom (in hotseat games, the side to move),
+    /// turned around if the player flipped the board.
+    fn orientation(&self) -> Orientation {
+        let black_bottom = match (self.white, self.black) {
+            _ if self.analysing() => false,
+            (Player::Engine, Player::Human) => true,
+            (Player::Human, Player::Human) => self.board.side_to_move() == ChessColor::Black,
+            _ => false,
+        };
+        let auto = if black_bottom { Orientation::BlackBottom } else { Orientation::WhiteBottom };
+        if self.board_flipped { auto.flip() } else { auto }
+    }
+
+    /// The position at the end of the line being played. The clocks run for
+    /// it, not for an earlier position shown while stepping through moves.
+    fn live_board(&self) -> Board {
+        if self.tree.at_end() { self.board } else { self.tree.line().board() }
+    }
+
+    /// An engine playing itself waits out the move delay so it can be followed.
+    fn engine_may_move(&self) -> bool {
+        let spectating = self.white == Player::Engine && self.black == Player::Engine;
+        !spectating || get_time() - self.moved_at >= self.ai_delay
+    }
+
+    fn analysing(&self) -> bool {
+        self.seating == Seating::Analysis && self.white == Player::Human && self.black == Player::Human
+    }
+
+    /// How the game ended, on time or on the board.
+    fn outcome(&self, history: &GameRecord) -> Option<Outcome> {
+        self.flag.or_else(|| history.outcome())
+    }
+
+    /// The engine's search budget: the difficulty's own limits, or a share
+    /// of what is left on its clock.
+    fn engine_limits(&self) -> crate::engine::SearchLimits {
+        let profile = self.difficulty.profile();
+        match &self.clock {
+            Some(clock) => profile.clock_limits(
+                clock.remaining(self.board.side_to_move()).as_millis() as u64,
+                clock.control().increment().as_millis() as u64,
+            ),
+            None => profile.limits(),
+        }
+    }
+}
+
+fn draw_text_centered(text: &str, x: f32, y: f32, size: f32) {
+    let d = measure_text(text, None, size as u16, 1.0);
+    draw_text(text, x - d.width/2.0, y, size, BLACK);
+}
+
+fn draw_menu() {
+    draw_rectangle(0.0, 0.0, BOARD_DIM+200.0, BOARD_DIM, WHITE);
+    draw_text_centered("Chess AI", BOARD_DIM/2.0, BOARD_DIM/2.0 - 210.0, 48.0);
+    draw_text_centered("Press Enter to Start", BOARD_DIM/2.0, BOARD_DIM/2.0 - 170.0, 24.0);
+    draw_text_centered("G: saved games", BOARD_DIM/2.0, BOARD_DIM/2.0 + 15.0, 20.0);
+    draw_text_centered("L: load PGN from clipboard, or drop a .pgn file", BOARD_DIM/2.0, BOARD_DIM/2.0 + 40.0, 20.0);
+}
+
+fn draw_difficulty_selection(difficulty: &mut Difficulty, keys_enabled: bool) {
+    let cx = BOARD_DIM / 2.0;
+    let y = BOARD_DIM / 2.0 - 120.0;
+    draw_text_centered("Use 1-3 to select difficulty:", cx, y, 20.0);
+    draw_text_centered(
+        match difficulty {
+            Difficulty::Easy => "1: Easy (selected)",
+            Difficulty::Medium => "2: Medium (selected)",
+            Difficulty::Hard => "3: Hard (selected)",
+        },
+        cx,
+        y + 30.0,
+        20.0,
+    );
+
+    if !keys_enabled {
+        return;
+    }
+    if is_key_pressed(KeyCode::Key1) {
+        *difficulty = Difficulty::Easy;
+    }
+    if is_key_pressed(KeyCode::Key2) {
+        *difficulty = Difficulty::Medium;
+    }
+    if is_key_pressed(KeyCode::Key3) {
+        *difficulty = Difficulty::Hard;
+    }
+}
+
+fn draw_seating_selection(seating: &mut Seating, keys_enabled: bool) {
+    let y = BOARD_DIM / 2.0 - 50.0;
+    draw_text_centered(
+        &format!("W/B/R/A/H/N: {} (White, Black, Random, AI vs AI, Hotseat, aNalysis)", seating.label()),
+        BOARD_DIM / 2.0,
+        y,
+        20.0,
+    );
+    if !keys_enabled {
+        return;
+    }
+    for (key, choice) in [
+        (KeyCode::W, Seating::White),
+        (KeyCode::B, Seating::Black),
+        (KeyCode::R, Seating::Random),
+        (KeyCode::A, Seating::AiVsAi),
+        (KeyCode::H, Seating::Hotseat),
+        (KeyCode::N, Seating::Analysis),
+    ] {
+        if is_key_pressed(key) {
+            *seating = choice;
+        }
+    }
+}
+
+/// One-line menu input, for the start position and the time control.
+#[derive(Default)]
+struct TextField {
+    text: String,
+    focused: bool,
+}
+
+/// The menu's FEN field: empty means the standard start position.
+fn start_position(fen: &str) -> Result<Board, FenError> {
+    if fen.trim().is_empty() {
+        Ok(Board::default())
+    } else {
+        parse_fen(fen)
+    }
+}
+
+/// Starts a fresh game from the start position of `record`.
+fn new_game(mut record: GameRecord, game: &mut ChessGame, history: &mut GameRecord) {
+    record.clear();
+    game.tree = GameTree::new(record);
+    show_cursor(game, history);
+    (game.white, game.black) = game.seating.players();
+    game.clock = game.time_control.map(ChessClock::new);
+    game.flag = None;
+    game.move_sources.clear();
+}
+
+/// Shows the position at the move tree's cursor, rebuilding the record,
+/// captures and last move from the moves that lead there.
+fn show_cursor(game: &mut ChessGame, history: &mut GameRecord) {
+    *history = game.tree.record().clone();
+    game.board = history.board();
+    game.selected_square = None;
+    game.dragging = false;
+    game.premove = None;
+    game.moved_at = get_time();
+    game.last_move = history.last();
+    (game.captured_white, game.captured_black) = history.captured();
+}
+
+/// Steps back to the last position a human was to move in, or a single
+/// move when nobody or everybody at the board is human. The moves stay in
+/// the tree to be redone. With only engine moves to take back it stays put,
+/// since the engine does not move from earlier positions.
+fn take_back(game: &mut ChessGame, history: &mut GameRecord) {
+    let (white, black) = (game.white, game.black);
+    let human = white == Player::Human || black == Player::Human;
+    let stepped = if human {
+        game.tree.take_back(|color| [white, black][color.to_index()] == Player::Human)
+    } else {
+        game.tree.back().is_some()
+    };
+    if !stepped {
+        game.notice = Some(("Nothing to take back".to_string(), get_time()));
+        return;
+    }
+    show_cursor(game, history);
+    autosave(game, history);
+}
+
+/// Plays a legal move for whichever side is to move.
+fn play_move(game: &mut ChessGame, history: &mut GameRecord, mv: ChessMove) {
+    if let Some(captured) = captured_piece(&game.board, mv) {
+        if game.board.side_to_move() == ChessColor::White {
+            game.captured_black.push(captured);
+        } else {
+            game.captured_white.push(captured);
+        }
+    }
+    if let Some(clock) = game.clock.as_mut() {
+        clock.press(game.board.side_to_move());
+    }
+    game.board = game.board.make_move_new(mv);
+    game.tree.play(mv);
+    history.push(mv);
+    game.last_move = Some(mv);
+    game.moved_at = get_time();
+    autosave(game, history);
+}
+
+/// Plays the engine's move, noting how it was chosen, then any premove the
+/// human queued meanwhile.
+fn play_engine_move(game: &mut ChessGame, history: &mut GameRecord, mv: ChessMove, source: MoveSource) {
+    game.move_sources.insert((game.board.get_hash(), mv), source);
+    play_move(game, history, mv);
+    if let Some(premove) = game.premove.take() {
+        if game.board.legal(premove) && history.outcome().is_none() {
+            play_move(game, history, premove);
+        } else {
+            game.notice = Some((format!("Premove {premove} is no longer legal"), get_time()));
+        }
+    }
+}
+
+/// How the engine chose each move of `line`; `None` for human moves.
+fn move_sources(game: &ChessGame, line: &GameRecord) -> Vec<Option<MoveSource>> {
+    let mut board = *line.start();
+    line.iter()
+        .map(|&mv| {
+            let source = game.move_sources.get(&(board.get_hash(), mv)).copied();
+            board = board.make_move_new(mv);
+            source
+        })
+        .collect()
+}
+
+/// The game in progress, as it would be written to a save slot.
+fn saved_game(game: &ChessGame, history: &GameRecord) -> SavedGame {
+    SavedGame {
+        record: history.clone(),
+        difficulty: game.difficulty,
+        engine_plays: [game.white == Player::Engine, game.black == Player::Engine],
+        analysis: game.analysing(),
+        clock: game.clock.clone(),
+    }
+}
+
+fn autosave(game: &ChessGame, history: &GameRecord) {
+    if let Err(err) = platform::write_save(AUTOSAVE_SLOT, &saved_game(game, history).to_pgn()) {
+        println!("Autosave failed: {err}");
+    }
+}
+
+/// Resumes the game saved in `slot`, posting a notice if it cannot be read.
+fn load_slot(slot: &str, game: &mut ChessGame, history: &mut GameRecord) -> bool {
+    let saved = platform::read_save(slot)
+        .ok_or_else(|| format!("no saved game {slot}"))
+        .and_then(|text| SavedGame::from_pgn(&text));
+    let saved = match saved {
+        Ok(saved) => saved,
+        Err(err) => {
+            game.notice = Some((format!("Could not load {slot}: {err}"), get_time()));
+            return false;
+        }
+    };
+    let player = |engine| if engine { Player::Engine } else { Player::Human };
+    (game.white, game.black) = (player(saved.engine_plays[0]), player(saved.engine_plays[1]));
+    game.seating = match (game.white, game.black) {
+        (Player::Human, Player::Engine) => Seating::White,
+        (Player::Engine, Player::Human) => Seating::Black,
+        (Player::Engine, Player::Engine) => Seating::AiVsAi,
+        (Player::Human, Player::Human) if saved.analysis => Seating::Analysis,
+        (Player::Human, Player::Human) => Seating::Hotseat,
+    };
+    game.difficulty = saved.difficulty;
+    game.time_control = saved.clock.as_ref().map(|clock| *clock.control());
+    game.clock = saved.clock;
+    game.flag = None;
+    game.move_sources.clear();
+    game.tree = GameTree::new(saved.record);
+    show_cursor(game, history);
+    game.notice = None;
+    true
+}
+
+/// Exits, saving the game first if one is under way.
+fn quit(state: &GameState, game: &ChessGame, history: &GameRecord) -> ! {
+    if !matches!(state, GameState::Menu | GameState::Replay | GameState::Saves) {
+        autosave(game, history);
+    }
+    std::process::exit(0)
+}
+
+/// Typing seen this frame, shared by every text field on the menu.
+struct FieldInput {
+    typed: Vec<char>,
+    pasted: Option<String>,
+    ctrl: bool,
+}
+
+impl FieldInput {
+    fn read() -> Self {
+        let ctrl = is_key_down(KeyCode::LeftControl)
+            || is_key_down(KeyCode::RightControl)
+            || is_key_down(KeyCode::LeftSuper)
+            || is_key_down(KeyCode::RightSuper);
+        // get_char_pressed pops the newest character first
+        let mut typed = Vec::new();
+        while let Some(c) = get_char_pressed() {
+            typed.push(c);
+        }
+        typed.reverse();
+        let pasted = (ctrl && is_key_pressed(KeyCode::V))
+            .then(miniquad::window::clipboard_get)
+            .flatten();
+        FieldInput { typed, pasted, ctrl }
+    }
+}
+
+/// Draws a labelled text box across the menu at `y` and applies `input` to
+/// it while it has focus. Clicking elsewhere takes the focus away.
+fn draw_text_field(field: &mut TextField, label: &str, y: f32, input: &FieldInput) {
+    let (x, w, h) = (40.0, BOARD_DIM + 120.0, 30.0);
+    draw_text(label, x, y - 8.0, 18.0, BLACK);
+    draw_rectangle(x, y, w, h, if field.focused { WHITE } else { LIGHTGRAY });
+    draw_rectangle_lines(x, y, w, h, 2.0, if field.focused { DARKBLUE } else { GRAY });
+
+    if is_mouse_button_pressed(MouseButton::Left) {
+        let (mx, my) = mouse_position();
+        field.focused = mx >= x && mx <= x + w && my >= y && my <= y + h;
+    }
+    if field.focused {
+        if let Some(pasted) = &input.pasted {
+            field.text = pasted.trim().to_string();
+        } else if !input.ctrl {
+            field.text.extend(input.typed.iter().filter(|&&c| c.is_ascii_graphic() || c == ' '));
+            if is_key_pressed(KeyCode::Backspace) {
+                field.text.pop();
+            }
+            if is_key_pressed(KeyCode::Escape) {
+                field.focused = false;
+            }
+        }
+    }
+
+    // Show the end of text too long for the box
+    let mut shown = field.text.as_str();
+    while measure_text(shown, None, 16, 1.0).width > w - 12.0 {
+        let mut chars = shown.chars();
+        chars.next();
+        shown = chars.as_str();
+    }
+    let caret = if field.focused && get_time() % 1.0 < 0.5 { "|" } else { "" };
+    draw_text(&format!("{shown}{caret}"), x + 6.0, y + 20.0, 16.0, BLACK);
+}
+
+/// The menu's time control field: empty means an untimed game.
+fn time_control(text: &str) -> Result<Option<TimeControl>, String> {
+    if text.trim().is_empty() {
+        Ok(None)
+    } else {
+        text.parse().map(Some)
+    }
+}
+
+/// The preset after the one in `text`, going back to untimed after the last.
+fn next_time_preset(text: &str) -> String {
+    let current = TimeControl::PRESETS.iter().position(|(_, tc)| tc.to_string() == text.trim());
+    let next = match current {
+        Some(i) => i + 1,
+        None if text.trim().is_empty() => 0,
+        None => TimeControl::PRESETS.len(),
+    };
+    TimeControl::PRESETS.get(next).map_or(String::new(), |(_, tc)| tc.to_string())
+}
+
+/// Runs the clock of the side to move at the end of the line for this
+/// frame. Returns `true` when a flag falls, after recording the result.
+fn run_clock(game: &mut ChessGame) -> bool {
+    let may_move = game.engine_may_move();
+    let live = game.live_board();
+    let Some(clock) = game.clock.as_mut() else { return false };
+    // A spectated engine's move delay is not its thinking time
+    if may_move {
+        clock.tick(live.side_to_move(), Duration::from_secs_f32(get_frame_time()));
+    }
+    match clock.flagged() {
+        Some(flagged) => {
+            game.flag = Some(timeout_outcome(&live, flagged));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Both clocks, the side at the top of the board first, with the running
+/// one highlighted.
+fn draw_clocks(clock: &ChessClock, to_move: ChessColor, orientation: Orientation, x: f32, y: f32) {
+    let top = if orientation == Orientation::WhiteBottom { ChessColor::Black } else { ChessColor::White };
+    for (i, color) in [top, !top].into_iter().enumerate() {
+        let y = y + i as f32 * 32.0;
+        let running = color == to_move;
+        draw_rectangle(x, y, 180.0, 28.0, if running { DARKBLUE } else { LIGHTGRAY });
+        let left = clock.remaining(color);
+        let text_color = if left < Duration::from_secs(10) {
+            RED
+        } else if running {
+            WHITE
+        } else {
+            BLACK
+        };
+        let name = if color == ChessColor::White { "White" } else { "Black" };
+        draw_text(name, x + 6.0, y + 20.0, 20.0, text_color);
+        let face = format_clock(left);
+        let width = measure_text(&face, None, 24, 1.0).width;
+        draw_text(&face, x + 174.0 - width, y + 21.0, 24.0, text_color);
+    }
+}
+
+/// Which side is drawn at the bottom of the board. Every square<->screen
+/// conversion goes through this.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Orientation {
+    WhiteBottom,
+    BlackBottom,
+}
+
+impl Orientation {
+    fn flip(self) -> Self {
+        match self {
+            Orientation::WhiteBottom => Orientation::BlackBottom,
+            Orientation::BlackBottom => Orientation::WhiteBottom,
+        }
+    }
+
+    /// `(column, row)` of the board cell `sq` is drawn in, counted from the
+    /// top-left.
+    fn cell(self, sq: Square) -> (usize, usize) {
+        let (file, rank) = (sq.get_file().to_index(), sq.get_rank().to_index());
+        match self {
+            Orientation::WhiteBottom => (file, 7 - rank),
+            Orientation::BlackBottom => (7 - file, rank),
+        }
+    }
+
+    /// Top-left corner of `sq` on screen.
+    fn square_origin(self, sq: Square) -> (f32, f32) {
+        let (col, row) = self.cell(sq);
+        (col as f32 * TILE_SIZE, row as f32 * TILE_SIZE)
+    }
+
+    /// The square under a screen position, if it is on the board.
+    fn square_at(self, x: f32, y: f32) -> Option<Square> {
+        let (col, row) = ((x / TILE_SIZE).floor(), (y / TILE_SIZE).floor());
+        if !(0.0..8.0).contains(&col) || !(0.0..8.0).contains(&row) {
+            return None;
+        }
+        let (col, row) = (col as usize, row as usize);
+        let (file, rank) = match self {
+            Orientation::WhiteBottom => (col, 7 - row),
+            Orientation::BlackBottom => (7 - col, row),
+        };
+        Some(Square::make_square(chess::Rank::from_index(rank), chess::File::from_index(file)))
+    }
+}
+
+fn draw_board(orientation: Orientation) {
+    for &sq in ALL_SQUARES.iter() {
+        let (r, f) = (sq.get_rank().to_index(), sq.get_file().to_index());
+        let c = if (r+f)%2==0 { LIGHTGRAY } else { DARKGRAY };
+        let (x, y) = orientation.square_origin(sq);
+        draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, c);
+
+        // Coordinates along the left and bottom edges, in the other square colour
+        let label = if c == LIGHTGRAY { DARKGRAY } else { LIGHTGRAY };
+        let (col, row) = orientation.cell(sq);
+        if col == 0 {
+            draw_text(&(r + 1).to_string(), x + 3.0, y + 14.0, 18.0, label);
+        }
+        if row == 7 {
+            let file = ((b'a' + f as u8) as char).to_string();
+            draw_text(&file, x + TILE_SIZE - 11.0, y + TILE_SIZE - 4.0, 18.0, label);
+        }
+    }
+}
+
+fn piece_key(color: ChessColor, piece: Piece) -> PieceKey {
+    match (color, piece) {
+        (ChessColor::White,Piece::Pawn)   => PieceKey::PawnWhite,
+        (ChessColor::White,Piece::Knight) => PieceKey::KnightWhite,
+        (ChessColor::White,Piece::Bishop) => PieceKey::BishopWhite,
+        (ChessColor::White,Piece::Rook)   => PieceKey::RookWhite,
+        (ChessColor::White,Piece::Queen)  => PieceKey::QueenWhite,
+        (ChessColor::White,Piece::King)   => PieceKey::KingWhite,
+        (ChessColor::Black,Piece::Pawn)   => PieceKey::PawnBlack,
+        (ChessColor::Black,Piece::Knight) => PieceKey::KnightBlack,
+        (ChessColor::Black,Piece::Bishop) => PieceKey::BishopBlack,
+        (ChessColor::Black,Piece::Rook)   => PieceKey::RookBlack,
+        (ChessColor::Black,Piece::Queen)  => PieceKey::QueenBlack,
+        (ChessColor::Black,Piece::King)   => PieceKey::KingBlack,
+    }
+}
+
+/// Draws every piece except the one on `lifted`, which is being dragged.
+fn draw_pieces(board: &Board, texs: &HashMap<PieceKey,Texture2D>, orientation: Orientation, lifted: Option<Square>) {
+    for &sq in ALL_SQUARES.iter() {
+        if Some(sq) == lifted {
+            continue;
+        }
+        if let (Some(pc), Some(clr)) = (board.piece_on(sq), board.color_on(sq)) {
+            let (x, y) = orientation.square_origin(sq);
+            draw_texture_ex(&texs[&piece_key(clr, pc)], x, y, WHITE, DrawTextureParams {
+                dest_size: Some(vec2(TILE_SIZE,TILE_SIZE)), ..Default::default()
+            });
+        }
+    }
+}
+
+/// The piece on `sq`, centred on the mouse.
+fn draw_dragged_piece(board: &Board, sq: Square, texs: &HashMap<PieceKey, Texture2D>) {
+    if let (Some(pc), Some(clr)) = (board.piece_on(sq), board.color_on(sq)) {
+        let (mx, my) = mouse_position();
+        draw_texture_ex(&texs[&piece_key(clr, pc)], mx - TILE_SIZE / 2.0, my - TILE_SIZE / 2.0, WHITE, DrawTextureParams {
+            dest_size: Some(vec2(TILE_SIZE, TILE_SIZE)), ..Default::default()
+        });
+    }
+}
+
+fn draw_premove(premove: Option<ChessMove>, orientation: Orientation) {
+    if let Some(mv) = premove {
+        for sq in [mv.get_source(), mv.get_dest()] {
+            let (x, y) = orientation.square_origin(sq);
+            draw_rectangle(x, y, TILE_SIZE, TILE_SIZE, Color::new(0.2, 0.4, 0.9, 0.35));
+        }
+    }
+}
+
+/// Marks pieces the opponent could win by taking them.
+fn draw_hanging(squares: &[Square], orientation: Orientation) {
+    for &sq in squares {
+        let (x, y) = orientation.square_origin(sq);
+        draw_rectangle_lines(x + 2.0, y + 2.0, TILE_SIZE - 4.0, TILE_SIZE - 4.0, 3.0, ORANGE);
+        draw_circle(x + TILE_SIZE - 12.0, y + 12.0, 9.0, ORANGE);
+        draw_text("!", x + TILE_SIZE - 14.5, y + 18.0, 20.0, WHITE);
+    }
+}
+
+fn highlight_selection(sel: Option<Square>, orientation: Orientation) {
+    if let Some(sq)=sel {
+        let (x, y) = orientation.square_origin(sq);
+        draw_rectangle_lines(x,y,TILE_SIZE,TILE_SIZE,3.0,RED);
+    }
+}
+
+fn draw_legal_moves(sq: Square, board: &Board, orientation: Orientation) {
+    for mv in MoveGen::new_legal(board) {
+        if mv.get_source()==sq {
+            let (x, y) = orientation.square_origin(mv.get_dest());
+            draw_circle(x + TILE_SIZE/2.0, y + TILE_SIZE/2.0, TILE_SIZE*0.1, Color::new(0.,0.8,0.,0.6));
+        }
+    }
+}
+
+fn draw_game_status(board: &Board) {
+    if board.status()==BoardStatus::Ongoing && board.checkers().popcnt()>0 {
+        draw_text_centered("Check!", BOARD_DIM/2.0, 20.0, 24.0);
+    }
+}
+
+/// Moves `side`'s pieces by clicking the piece and then its destination,
+/// or by dragging it there. Returns `(from, to)` once a move is made; it
+/// may still be illegal, in which case the piece snaps back.
+fn handle_mouse(game: &mut ChessGame, side: ChessColor) -> Option<(Square, Square)> {
+    let (mx, my) = mouse_position();
+    let under = game.orientation().square_at(mx, my);
+    if is_mouse_button_pressed(MouseButton::Left) {
+        let sq = under?;
+        if game.board.color_on(sq) == Some(side) {
+            game.selected_square = Some(sq);
+            game.dragging = true;
+            return None;
+        }
+        let from = game.selected_square.take()?;
+        return Some((from, sq));
+    }
+    // A release can be missed while paused, so go by the button state
+    if game.dragging && !is_mouse_button_down(MouseButton::Left) {
+        game.dragging = false;
+        let from = game.selected_square?;
+        match under {
+            // Dropped where it was picked up: stays selected for a click
+            Some(sq) if sq == from => {}
+            Some(sq) => {
+                game.selected_square = None;
+                return Some((from, sq));
+            }
+            None => game.selected_square = None,
+        }
+    }
+    None
+}
+
+/// Whether moving the piece on `from` to `to` is a pawn reaching the last rank.
+fn needs_promotion(board: &Board, from: Square, to: Square) -> bool {
+    let rank = to.get_rank().to_index();
+    board.piece_on(from) == Some(Piece::Pawn) && (rank == 0 || rank == 7)
+}
+
+fn draw_promotion_ui(
+    from: Square,
+    to: Square,
+    textures: &HashMap<PieceKey, Texture2D>,
+    state: &mut GameState,
+    game: &mut ChessGame,
+    history: &mut GameRecord,
+) {
+    let cx = BOARD_DIM / 2.0;
+    let cy = BOARD_DIM / 2.0;
+    let sz = TILE_SIZE;
+    for (i, &piece) in PROMO_PIECES.iter().enumerate() {
+        let x = cx + (i as f32 - 1.5) * (sz + 10.0);
+        let y = cy - sz / 2.0;
+        let key = match (game.board.side_to_move(), piece) {
+            (ChessColor::White, Piece::Queen)  => PieceKey::QueenWhite,
+            (ChessColor::White, Piece::Rook)   => PieceKey::RookWhite,
+            (ChessColor::White, Piece::Bishop) => PieceKey::BishopWhite,
+            (ChessColor::White, Piece::Knight) => PieceKey::KnightWhite,
+            (ChessColor::Black, Piece::Queen)  => PieceKey::QueenBlack,
+            (ChessColor::Black, Piece::Rook)   => PieceKey::RookBlack,
+            (ChessColor::Black, Piece::Bishop) => PieceKey::BishopBlack,
+            (ChessColor::Black, Piece::Knight) => PieceKey::KnightBlack,
+            _ => continue,
+        };
+        draw_texture_ex(
+            &textures[&key],
+            x, y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(sz, sz)),
+                ..Default::default()
+            },
+        );
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mx, my) = mouse_position();
+            if mx >= x && mx <= x + sz && my >= y && my <= y + sz {
+                play_move(game, history, ChessMove::new(from, to, Some(piece)));
+                *state = GameState::Playing;
+                break;
+            }
+        }
+    }
+}
+
+fn draw_pause_menu(
+    state: &mut GameState,
+    game: &mut ChessGame,
+    history: &mut GameRecord,
+) {
+    draw_rectangle(0.0, 0.0, BOARD_DIM + 200.0, BOARD_DIM, BLACK.with_alpha(0.5));
+    let bw = 160.0;
+    let bh = 50.0;
+    let cx = (BOARD_DIM + 200.0) / 2.0;
+    let labels = ["Resume", "Restart", "Undo", "Save Game", "Save PGN", "Exit"];
+    let start_y = BOARD_DIM / 2.0 - (labels.len() as f32 * (bh + 10.0)) / 2.0;
+
+    for (i, &lbl) in labels.iter().enumerate() {
+        let x = cx - bw / 2.0;
+        let y = start_y + i as f32 * (bh + 10.0);
+        draw_rectangle(x, y, bw, bh, LIGHTGRAY);
+        draw_text_centered(lbl, x + bw / 2.0, y + bh / 2.0 + 8.0, 24.0);
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mx, my) = mouse_position();
+            if mx >= x && mx <= x + bw && my >= y && my <= y + bh {
+                match lbl {
+                    "Resume" => *state = GameState::Playing,
+                    "Restart" => {
+                        new_game(history.clone(), game, history);
+                        *state = GameState::Playing;
+                    }
+                    "Undo" => {
+                        take_back(game, history);
+                        *state = GameState::Playing;
+                    }
+                    "Save Game" => *state = GameState::SaveAs,
+                    "Save PGN" => {
+                        save_pgn(game, history);
+                        *state = GameState::Playing;
+                    }
+                    "Exit" => quit(state, game, history),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+
+fn draw_game_over_ui(
+    state: &mut GameState,
+    game: &mut ChessGame,
+    history: &mut GameRecord,
+) {
+    let msg = match game.outcome(history) {
+        Some(Outcome::Checkmate { winner }) if game.player(winner) != game.player(!winner) => {
+            if game.player(winner) == Player::Engine {
+                "You were checkmated!".to_string()
+            } else {
+                "Opponent was checkmated!".to_string()
+            }
+        }
+        Some(outcome) => outcome.to_string(),
+        None => "Game Over".to_string(),
+    };
+
+    draw_overlay(&msg);
+
+    let bw = 120.0;
+    let bh = 40.0;
+    let rx = BOARD_DIM / 2.0 - bw - 10.0;
+    let ex = BOARD_DIM / 2.0 + 10.0;
+    let y  = BOARD_DIM / 2.0 + 10.0;
+
+    draw_rectangle(rx, y, bw, bh, LIGHTGRAY);
+    draw_text_centered("Restart", rx + bw/2.0, y + bh/2.0 + 5.0, 24.0);
+
+    draw_rectangle(ex, y, bw, bh, LIGHTGRAY);
+    draw_text_centered("Exit", ex + bw/2.0, y + bh/2.0 + 5.0, 24.0);
+
+    // A lost flag cannot be taken back, anything decided on the board can
+    if game.flag.is_none() && !history.is_empty() {
+        draw_text_centered("Left: take back", BOARD_DIM / 2.0, y + bh + 30.0, 20.0);
+        if is_key_pressed(KeyCode::Left) {
+            take_back(game, history);
+            *state = GameState::Playing;
+        }
+    }
+
+    if is_mouse_button_pressed(MouseButton::Left) {
+        let (mx, my) = mouse_position();
+        if mx >= rx && mx <= rx + bw && my >= y && my <= y + bh {
+            new_game(history.clone(), game, history);
+            *state = GameState::Playing;
+        }
+        if mx >= ex && mx <= ex + bw && my >= y && my <= y + bh {
+            quit(state, game, history);
+        }
+    }
+}
+
+fn draw_last_move(last_move: Option<ChessMove>, orientation: Orientation) {
+    if let Some(mv) = last_move {
+        let (from, to) = (mv.get_source(), mv.get_dest());
+        for &sq in &[from, to] {
+            let (x, y) = orientation.square_origin(sq);
+            draw_rectangle_lines(x, y, TILE_SIZE, TILE_SIZE, 4.0, YELLOW);
+        }
+    }
+}
+
+fn draw_captured_pieces(
+    captured_white: &[Piece],
+    captured_black: &[Piece],
+    textures: &HashMap<PieceKey, Texture2D>,
+) {
+    let panel_x = BOARD_DIM + 10.0;
+    let icon_size = 30.0;
+    let spacing = 5.0;
+    let per_row = 4;
+
+    // Define bottom area starting point
+    let y_start = BOARD_DIM - 10.0; // Start from very bottom
+
+    // First draw captured White pieces (captured by Black)
+    let mut x = panel_x;
+    let mut y = y_start - icon_size; // go up
+    for (i, &piece) in captured_white.iter().enumerate() {
+        let key = match piece {
+            Piece::Pawn => PieceKey::PawnWhite,
+            Piece::Knight => PieceKey::KnightWhite,
+            Piece::Bishop => PieceKey::BishopWhite,
+            Piece::Rook => PieceKey::RookWhite,
+            Piece::Queen => PieceKey::QueenWhite,
+            Piece::King => PieceKey::KingWhite,
+        };
+
+        draw_texture_ex(
+            &textures[&key],
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(icon_size, icon_size)),
+                ..Default::default()
+            },
+        );
+
+        if (i + 1) % per_row == 0 {
+            x = panel_x;
+            y -= icon_size + spacing;
+        } else {
+            x += icon_size + spacing;
+        }
+    }
+
+    // Then draw captured Black pieces (captured by White)
+    // start higher so it's separate
+    let captured_white_rows = captured_white.len().div_ceil(per_row);
+    y -= 20.0; // small gap between white and black captured
+    y -= (icon_size + spacing) * captured_white_rows as f32;
+
+    x = panel_x;
+    for (i, &piece) in captured_black.iter().enumerate() {
+        let key = match piece {
+            Piece::Pawn => PieceKey::PawnBlack,
+            Piece::Knight => PieceKey::KnightBlack,
+            Piece::Bishop => PieceKey::BishopBlack,
+            Piece::Rook => PieceKey::RookBlack,
+            Piece::Queen => PieceKey::QueenBlack,
+            Piece::King => PieceKey::KingBlack,
+        };
+
+        draw_texture_ex(
+            &textures[&key],
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(icon_size, icon_size)),
+                ..Default::default()
+            },
+        );
+
+        if (i + 1) % per_row == 0 {
+            x = panel_x;
+            y -= icon_size + spacing;
+        } else {
+            x += icon_size + spacing;
+        }
+    }
+}
+
+
+
+/// A thin bar down the board's right edge, White's share growing from
+/// White's side of the board. `score` is from White's point of view.
+fn draw_eval_bar(score: i32, orientation: Orientation) {
+    let (x, width) = (BOARD_DIM + 1.0, 7.0);
+    let share = match mate_in(score) {
+        Some(moves) if moves > 0 => 1.0,
+        Some(_) => 0.0,
+        None => 0.5 + score.clamp(-1000, 1000) as f32 / 2000.0,
+    };
+    let white_height = BOARD_DIM * share;
+    draw_rectangle(x, 0.0, width, BOARD_DIM, DARKGRAY);
+    let white_top = match orientation {
+        Orientation::WhiteBottom => BOARD_DIM - white_height,
+        Orientation::BlackBottom => 0.0,
+    };
+    draw_rectangle(x, white_top, width, white_height, WHITE);
+    draw_line(x, BOARD_DIM / 2.0, x + width, BOARD_DIM / 2.0, 1.0, RED);
+}
+
+/// Converts a score for the side to move on `board` to White's point of view.
+fn white_score(score: i32, board: &Board) -> i32 {
+    if board.side_to_move() == ChessColor::White { score } else { -score }
+}
+
+/// `+0.35`, or `#3` / `#-3` for mates, from White's point of view.
+fn format_score(white_score: i32) -> String {
+    match mate_in(white_score) {
+        Some(moves) => format!("#{moves}"),
+        None => format!("{:+.2}", white_score as f32 / 100.0),
+    }
+}
+
+/// `950`, `12.3k` or `4.5M`.
+fn format_count(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{:.1}k", n as f64 / 1e3),
+        _ => format!("{:.1}M", n as f64 / 1e6),
+    }
+}
+
+/// The first move of each analysis line as an arrow, the best one boldest.
+fn draw_analysis_arrows(result: &SearchResult, board: &Board, orientation: Orientation) {
+    for (rank, line) in result.lines.iter().enumerate().rev() {
+        let Some(&mv) = line.pv.first().filter(|&&mv| board.legal(mv)) else { continue };
+        let alpha = if rank == 0 { 0.8 } else { 0.45 };
+        draw_arrow(mv.get_source(), mv.get_dest(), orientation, Color::new(0.1, 0.45, 0.9, alpha));
+    }
+}
+
+fn draw_arrow(from: Square, to: Square, orientation: Orientation, color: Color) {
+    let centre = |sq| {
+        let (x, y) = orientation.square_origin(sq);
+        vec2(x + TILE_SIZE / 2.0, y + TILE_SIZE / 2.0)
+    };
+    let (start, end) = (centre(from), centre(to));
+    let dir = (end - start).normalize_or_zero();
+    let head = TILE_SIZE * 0.3;
+    let neck = end - dir * head;
+    draw_line(start.x, start.y, neck.x, neck.y, TILE_SIZE * 0.12, color);
+    let side = vec2(-dir.y, dir.x) * head * 0.6;
+    draw_triangle(end, neck + side, neck - side, color);
+}
+
+/// Score, search statistics and the analysis lines in SAN, from `y` down.
+/// Returns the y below the last line drawn.
+fn draw_analysis_panel(result: &SearchResult, board: &Board, lines: usize, x: f32, y: f32) -> f32 {
+    let score = format_score(white_score(result.score, board));
+    draw_text(&format!("Eval {score}  depth {}", result.depth), x, y, 20.0, BLACK);
+    let nps = result.nodes * 1000 / result.elapsed_ms.max(1);
+    let stats = format!("{} nodes, {} nps", format_count(result.nodes), format_count(nps));
+    draw_text(&stats, x, y + 18.0, 16.0, DARKGRAY);
+
+    let mut y = y + 40.0;
+    for line in &result.lines {
+        let mut text = format_score(white_score(line.score, board));
+        let mut position = *board;
+        for &mv in &line.pv {
+            if !position.legal(mv) {
+                break;
+            }
+            let san = san::to_san(&position, mv);
+            if measure_text(&format!("{text} {san}"), None, 16, 1.0).width > 180.0 {
+                break;
+            }
+            text = format!("{text} {san}");
+            position = position.make_move_new(mv);
+        }
+        draw_text(&text, x, y, 16.0, BLACK);
+        y += 18.0;
+    }
+    draw_text(&format!("Lines: {lines} (+/-)"), x, y, 16.0, DARKGRAY);
+    y + 24.0
+}
+
+/// Writes the game so far as PGN and posts where it went as a notice.
+fn save_pgn(game: &mut ChessGame, history: &GameRecord) {
+    let name = |player| match player {
+        Player::Human => "Player".to_string(),
+        Player::Engine => format!("Chess AI ({})", game.difficulty),
+    };
+    let mut tags = PgnTags::new(name(game.white), name(game.black));
+    tags.result = GameResult::from_outcome(game.outcome(history));
+    let text = pgn::export(history, &tags);
+    let stamp = web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let name = format!("chess_ai_{stamp}.pgn");
+    let message = match platform::save_text_file(&name, &text) {
+        Ok(msg) => msg,
+        Err(err) => err,
+    };
+    println!("{message}");
+    game.notice = Some((message, get_time()));
+}
+
+/// Parses PGN text for the replay viewer, posting a notice on failure.
+fn load_pgn(text: &str, game: &mut ChessGame) -> Option<Replay> {
+    let message = match pgn::parse(text) {
+        Ok(games) if games.is_empty() => "No games found in PGN".to_string(),
+        Ok(games) => {
+            game.notice = None;
+            return Some(Replay { games, game: 0, ply: 0, orientation: Orientation::WhiteBottom });
+        }
+        Err(err) => format!("PGN error: {err}"),
+    };
+    println!("{message}");
+    game.notice = Some((message, get_time()));
+    None
+}
+
+/// Draws the move list as numbered White/Black pairs inside the rows
+/// `(top, bottom, line_height, scroll)`, highlighting the move that led to
+/// `shown_ply` and colouring the engine's moves by `sources`, indexed by
+/// ply. Returns the ply after a move that was clicked.
+fn draw_move_list(
+    pairs: &[MovePair],
+    sources: &[Option<MoveSource>],
+    black_first: bool,
+    shown_ply: usize,
+    x: f32,
+    width: f32,
+    (top, bottom, line_height, scroll): (f32, f32, f32, f32),
+) -> Option<usize> {
+    let (mx, my) = mouse_position();
+    let clicked = is_mouse_button_pressed(MouseButton::Left) && mx >= x && mx < x + width - 8.0;
+    let columns = [x + 38.0, x + 104.0];
+    let mut chosen = None;
+    for (i, pair) in pairs.iter().enumerate() {
+        let row_top = top + 4.0 + i as f32 * line_height + scroll;
+        if row_top + line_height < top || row_top > bottom - line_height {
+            continue;
+        }
+        let baseline = row_top + 16.0;
+        draw_text(&format!("{}.", pair.number), x + 5.0, baseline, 20.0, DARKGRAY);
+        for (side, san) in [&pair.white, &pair.black].into_iter().enumerate() {
+            // Ply count after this move, counted from the start of the game
+            let after = 2 * i + side + 1 - black_first as usize;
+            let col_x = columns[side];
+            let Some(san) = san else {
+                if side == 0 {
+                    draw_text("...", col_x, baseline, 20.0, DARKGRAY);
+                }
+                continue;
+            };
+            if after == shown_ply {
+                draw_rectangle(col_x - 3.0, row_top, 64.0, line_height - 2.0, Color::new(1.0, 0.9, 0.4, 0.8));
+            }
+            let color = sources.get(after - 1).copied().flatten().map_or(BLACK, MoveSource::color);
+            draw_text(san, col_x, baseline, 20.0, color);
+            let in_row = my >= row_top.max(top) && my < (row_top + line_height).min(bottom);
+            if clicked && in_row && mx >= col_x - 3.0 && mx < col_x + 61.0 {
+                chosen = Some(after);
+            }
+        }
+    }
+    chosen
+}
+
+/// Draws the saved games, one per row. Returns `true` when a row was
+/// clicked, after selecting it.
+fn draw_save_list(list: &mut SaveList) -> bool {
+    let (x, w, row) = (40.0, BOARD_DIM + 120.0, 30.0);
+    draw_text("Saved games", x, 50.0, 36.0, BLACK);
+    draw_text("Up/Down: choose, Enter or click: load, Delete: remove, Esc: back", x, 80.0, 18.0, DARKGRAY);
+    if list.slots.is_empty() {
+        draw_text("No saved games yet. Use Save Game in the pause menu.", x, 130.0, 20.0, BLACK);
+        return false;
+    }
+
+    let mut clicked = false;
+    // Keep the selected row on screen
+    let visible = ((BOARD_DIM - 160.0) / row) as usize;
+    let first = list.selected.saturating_sub(visible - 1);
+    for (i, slot) in list.slots.iter().enumerate().skip(first).take(visible) {
+        let y = 100.0 + (i - first) as f32 * row;
+        if i == list.selected {
+            draw_rectangle(x, y, w, row - 4.0, LIGHTGRAY);
+        }
+        draw_text(slot, x + 8.0, y + 19.0, 20.0, BLACK);
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mx, my) = mouse_position();
+            if mx >= x && mx <= x + w && my >= y && my <= y + row {
+                list.selected = i;
+                clicked = true;
+            }
+        }
+    }
+    clicked
+}
+
+fn draw_replay_panel(view: &Replay) {
+    let x = BOARD_DIM + 10.0;
+    let pgn = &view.games[view.game];
+    let record = &pgn.record;
+    let white = pgn.tag("White").unwrap_or("?");
+    let black = pgn.tag("Black").unwrap_or("?");
+
+    draw_text("Replay", x, 30.0, 28.0, BLACK);
+    if view.games.len() > 1 {
+        draw_text(&format!("Game {}/{}", view.game + 1, view.games.len()), x, 56.0, 20.0, DARKGRAY);
+    }
+    draw_text(&format!("W: {white}"), x, 84.0, 18.0, BLACK);
+    draw_text(&format!("B: {black}"), x, 104.0, 18.0, BLACK);
+    draw_text(&format!("Result: {}", pgn.result), x, 124.0, 18.0, BLACK);
+
+    draw_text(&format!("Ply {}/{}", view.ply, record.len()), x, 160.0, 20.0, BLACK);
+    if let Some(i) = view.ply.checked_sub(1) {
+        let before = record.board_at(i);
+        let black_first = record.start().side_to_move() == ChessColor::Black;
+        let number = (i + black_first as usize) / 2 + record.start_fullmove_number() as usize;
+        let dots = if before.side_to_move() == ChessColor::White { "." } else { "..." };
+        let san = san::to_san(&before, record.moves()[i]);
+        draw_text(&format!("{number}{dots} {san}"), x, 184.0, 20.0, BLACK);
+    }
+
+    let help = ["Left/Right: step", "Home/End: jump", "Up/Down: game", "F: flip board", "T: take over", "Esc: menu"];
+    for (i, line) in help.iter().enumerate() {
+        draw_text(line, x, 230.0 + i as f32 * 20.0, 18.0, DARKGRAY);
+    }
+}
+
+/// Shows the current notice for a few seconds, wrapped to the side panel.
+fn draw_notice(notice: &mut Option<(String, f64)>, x: f32, y: f32) {
+    const NOTICE_SECS: f64 = 4.0;
+    let Some((text, posted)) = notice.as_ref() else { return };
+    if get_time() - posted > NOTICE_SECS {
+        *notice = None;
+        return;
+    }
+    draw_wrapped(text, x, y);
+}
+
+/// Small grey text wrapped to the side panel's width.
+fn draw_wrapped(text: &str, x: f32, y: f32) {
+    let mut line = String::new();
+    let mut y = y;
+    for c in text.chars() {
+        line.push(c);
+        if measure_text(&line, None, 16, 1.0).width > 180.0 {
+            let last = line.pop().unwrap();
+            draw_text(&line, x, y, 16.0, DARKGRAY);
+            line = last.to_string();
+            y += 18.0;
+        }
+    }
+    draw_text(&line, x, y, 16.0, DARKGRAY);
+}
+
+fn draw_thinking_indicator() {
+    let dots = (get_time() * 3.0) as usize % 4;
+    let text = format!("Thinking{}", ".".repeat(dots));
+    draw_text(&text, BOARD_DIM + 60.0, 36.0, 24.0, DARKGRAY);
+}
+
+fn draw_overlay(msg: &str) {
+    draw_rectangle(0.0, 0.0, BOARD_DIM + 200.0, BOARD_DIM, BLACK.with_alpha(0.5));
+    draw_text_centered(msg, BOARD_DIM/2.0, BOARD_DIM/2.0, 36.0);
+}
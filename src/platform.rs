@@ -0,0 +1,119 @@
+//! The few things the GUI does differently in the browser.
+
+/// Saves `contents` for the user under `file_name`: written to the working
+/// directory on desktop, offered as a download on the web. Returns a description of where it went.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_text_file(file_name: &str, contents: &str) -> Result<String, String> {
+    std::fs::write(file_name, contents).map_err(|e| format!("could not write {file_name}: {e}"))?;
+    let path = std::fs::canonicalize(file_name).unwrap_or_else(|_| file_name.into());
+    Ok(format!("Saved {}", path.display()))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_text_file(file_name: &str, contents: &str) -> Result<String, String> {
+    use wasm_bindgen::JsCast;
+
+    let fail = |what: &str| format!("download failed: {what}");
+    let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(contents));
+    let blob = web_sys::Blob::new_with_str_sequence(&parts).map_err(|_| fail("blob"))?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(|_| fail("object url"))?;
+
+    let document = web_sys::window().and_then(|w| w.document()).ok_or_else(|| fail("no document"))?;
+    let anchor: web_sys::HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(|_| fail("anchor"))?
+        .dyn_into()
+        .map_err(|_| fail("anchor"))?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    let _ = web_sys::Url::revoke_object_url(&url);
+    Ok(format!("Downloaded {file_name}"))
+}
+
+/// Saved games live in this directory on desktop, one `<slot>.pgn` each.
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_DIR: &str = "saves";
+
+/// Prefix of the localStorage keys holding saved games on the web.
+#[cfg(target_arch = "wasm32")]
+const SAVE_KEY_PREFIX: &str = "chess_ai_save:";
+
+/// Slot names become file names, so keep them to a safe set of characters.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_path(slot: &str) -> std::path::PathBuf {
+    let name: String = slot
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || " -_.".contains(c) { c } else { '_' })
+        .collect();
+    std::path::Path::new(SAVE_DIR).join(format!("{}.pgn", name.trim_start_matches('.')))
+}
+
+/// Stores a saved game under `slot`, replacing any earlier one.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_save(slot: &str, contents: &str) -> Result<(), String> {
+    std::fs::create_dir_all(SAVE_DIR).map_err(|e| format!("could not create {SAVE_DIR}: {e}"))?;
+    let path = save_path(slot);
+    std::fs::write(&path, contents).map_err(|e| format!("could not write {}: {e}", path.display()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_save(slot: &str) -> Option<String> {
+    std::fs::read_to_string(save_path(slot)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn delete_save(slot: &str) {
+    let _ = std::fs::remove_file(save_path(slot));
+}
+
+/// Names of every saved game, sorted.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn list_saves() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(SAVE_DIR) else { return Vec::new() };
+    let mut slots: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let is_pgn = path.extension().is_some_and(|ext| ext == "pgn");
+            is_pgn.then(|| path.file_stem()?.to_str().map(str::to_string)).flatten()
+        })
+        .collect();
+    slots.sort();
+    slots
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write_save(slot: &str, contents: &str) -> Result<(), String> {
+    let storage = local_storage().ok_or("local storage is unavailable")?;
+    storage
+        .set_item(&format!("{SAVE_KEY_PREFIX}{slot}"), contents)
+        .map_err(|_| "local storage is full".to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read_save(slot: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("{SAVE_KEY_PREFIX}{slot}")).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn delete_save(slot: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&format!("{SAVE_KEY_PREFIX}{slot}"));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn list_saves() -> Vec<String> {
+    let Some(storage) = local_storage() else { return Vec::new() };
+    let mut slots: Vec<String> = (0..storage.length().unwrap_or(0))
+        .filter_map(|i| storage.key(i).ok()?)
+        .filter_map(|key| key.strip_prefix(SAVE_KEY_PREFIX).map(str::to_string))
+        .collect();
+    slots.sort();
+    slots
+}
@@ -0,0 +1,95 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove, Square};
+use chess_ai_app::engine::book::{polyglot_key, OpeningBook};
+use chess_ai_app::engine::{Difficulty, GameRecord};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn board(fen: &str) -> Board {
+    Board::from_str(fen).expect("valid FEN")
+}
+
+fn mv(text: &str) -> ChessMove {
+    ChessMove::from_str(text).unwrap()
+}
+
+/// One `.bin` entry: key, move (target in bits 0-5, origin in 6-11),
+/// weight and an unused learn field.
+fn entry(board: &Board, from: Square, to: Square, weight: u16) -> Vec<u8> {
+    let raw = (to.to_index() | from.to_index() << 6) as u16;
+    let mut bytes = polyglot_key(board).to_be_bytes().to_vec();
+    bytes.extend(raw.to_be_bytes());
+    bytes.extend(weight.to_be_bytes());
+    bytes.extend([0; 4]);
+    bytes
+}
+
+#[test]
+fn keys_match_the_polyglot_reference() {
+    // From the Polyglot book format description
+    let positions = [
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 0x463b96181691fc9c),
+        ("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1", 0x823c9b50fd114196),
+        ("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2", 0x0756b94461c50fb0),
+        ("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2", 0x662fafb965db29d4),
+        ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", 0x22a48b5a8e47ff78),
+        ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3", 0x652a607ca3f242c1),
+        ("rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4", 0x00fdd303c946bdd9),
+        ("rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3", 0x3c8123ea7b067637),
+        ("rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4", 0x5c3f9b829b279560),
+    ];
+    for (fen, key) in positions {
+        assert_eq!(polyglot_key(&board(fen)), key, "{fen}");
+    }
+}
+
+#[test]
+fn book_moves_are_decoded_and_weighted() {
+    let start = Board::default();
+    let castling = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    let mut bytes = Vec::new();
+    // Written out of order; the book sorts them
+    bytes.extend(entry(&castling, Square::E1, Square::H1, 5));
+    bytes.extend(entry(&start, Square::D2, Square::D4, 1));
+    bytes.extend(entry(&start, Square::E2, Square::E4, 3));
+    bytes.extend(entry(&start, Square::E2, Square::E5, 9)); // not legal here
+    bytes.extend(entry(&start, Square::G1, Square::F3, 0)); // never to be played
+    let book = OpeningBook::from_bytes(&bytes).unwrap();
+    assert_eq!(book.len(), 5);
+
+    let moves: Vec<(ChessMove, u16)> = book.moves(&start).iter().map(|m| (m.mv, m.weight)).collect();
+    assert_eq!(moves, [(mv("e2e4"), 3), (mv("d2d4"), 1)]);
+    // Castling is stored as the king taking its rook
+    assert_eq!(book.moves(&castling)[0].mv, mv("e1g1"));
+    assert!(book.moves(&board("4k3/8/8/8/8/8/8/4K3 w - - 0 1")).is_empty());
+
+    let mut rng = StdRng::seed_from_u64(3);
+    assert_eq!(book.pick(&start, 0.0, &mut rng), Some(mv("e2e4")));
+    let picks: Vec<ChessMove> = (0..200).filter_map(|_| book.pick(&start, 1.0, &mut rng)).collect();
+    let e4 = picks.iter().filter(|&&m| m == mv("e2e4")).count();
+    assert_eq!(picks.len(), 200);
+    assert!((120..190).contains(&e4), "e4 picked {e4} times in 200");
+
+    assert!(OpeningBook::from_bytes(&bytes[..20]).is_err());
+    assert!(OpeningBook::from_bytes(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn difficulty_limits_how_long_the_book_is_followed() {
+    let start = Board::default();
+    let bytes = entry(&start, Square::E2, Square::E4, 1);
+    let book = OpeningBook::from_bytes(&bytes).unwrap();
+    let mut rng = StdRng::seed_from_u64(1);
+
+    let easy = Difficulty::Easy.profile();
+    assert_eq!(easy.book_move(&book, &GameRecord::default(), &mut rng), Some(mv("e2e4")));
+    let mut game = GameRecord::default();
+    for text in ["g1f3", "g8f6", "f3g1", "f6g8"].iter().cycle().take(easy.book_plies.next_multiple_of(4)) {
+        game.push(mv(text));
+    }
+    // Back at the start position, but too far into the game for Easy
+    assert_eq!(game.board(), start);
+    assert_eq!(easy.book_move(&book, &game, &mut rng), None);
+    assert!(Difficulty::Hard.profile().book_plies > easy.book_plies);
+}
@@ -0,0 +1,125 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chess::{Board, Color as ChessColor};
+use chess_ai_app::engine::clock::{format_clock, Bonus, ChessClock, TimeControl};
+use chess_ai_app::engine::rules::{timeout_outcome, DrawReason, Outcome};
+use chess_ai_app::engine::Difficulty;
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+#[test]
+fn time_controls_parse_and_print() {
+    for text in ["3+2", "5+0", "5 d3", "5 b3", "15+10"] {
+        assert_eq!(TimeControl::from_str(text).unwrap().to_string(), text);
+    }
+    assert_eq!("10".parse(), Ok(TimeControl::fischer(10, 0)));
+    assert_eq!("5d3".parse(), Ok(TimeControl::new(300, Bonus::SimpleDelay(secs(3)))));
+    assert_eq!("0.5+1".parse::<TimeControl>().unwrap().base, secs(30));
+    for bad in ["", "+2", "0+1", "3+x", "three"] {
+        assert!(TimeControl::from_str(bad).is_err(), "{bad}");
+    }
+    for (_, preset) in TimeControl::PRESETS {
+        assert_eq!(preset.to_string().parse(), Ok(preset));
+    }
+}
+
+#[test]
+fn increment_is_added_per_move() {
+    let mut clock = ChessClock::new(TimeControl::fischer(3, 2));
+    clock.tick(ChessColor::White, secs(5));
+    clock.press(ChessColor::White);
+    assert_eq!(clock.remaining(ChessColor::White), secs(177));
+    assert_eq!(clock.remaining(ChessColor::Black), secs(180));
+}
+
+#[test]
+fn delays_only_charge_time_beyond_the_delay() {
+    let control = TimeControl::new(60, Bonus::SimpleDelay(secs(3)));
+    let mut clock = ChessClock::new(control);
+    clock.tick(ChessColor::White, secs(2));
+    assert_eq!(clock.remaining(ChessColor::White), secs(60));
+    clock.tick(ChessColor::White, secs(2));
+    clock.press(ChessColor::White);
+    assert_eq!(clock.remaining(ChessColor::White), secs(59));
+
+    // Bronstein refunds the time used, but never more than the delay
+    let mut clock = ChessClock::new(TimeControl::new(60, Bonus::Bronstein(secs(3))));
+    clock.tick(ChessColor::White, secs(2));
+    clock.press(ChessColor::White);
+    assert_eq!(clock.remaining(ChessColor::White), secs(60));
+    clock.tick(ChessColor::Black, secs(10));
+    clock.press(ChessColor::Black);
+    assert_eq!(clock.remaining(ChessColor::Black), secs(53));
+}
+
+#[test]
+fn flag_falls_and_stays_down() {
+    let mut clock = ChessClock::new(TimeControl::fischer(1, 5));
+    clock.tick(ChessColor::White, secs(59));
+    assert_eq!(clock.flagged(), None);
+    clock.tick(ChessColor::White, secs(2));
+    assert_eq!(clock.flagged(), Some(ChessColor::White));
+    clock.press(ChessColor::White);
+    assert_eq!(clock.flagged(), Some(ChessColor::White));
+
+    assert_eq!(format_clock(secs(125)), "2:05");
+    assert_eq!(format_clock(Duration::from_millis(9_450)), "9.4");
+}
+
+#[test]
+fn timeout_is_a_draw_without_mating_material() {
+    let board = |fen: &str| Board::from_str(fen).unwrap();
+    assert_eq!(
+        timeout_outcome(&board("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"), ChessColor::Black),
+        Outcome::Timeout { winner: ChessColor::White }
+    );
+    // A lone knight or bishop still mates with the other side's help
+    assert_eq!(
+        timeout_outcome(&board("4k3/8/8/8/8/8/r7/4K1N1 w - - 0 1"), ChessColor::Black),
+        Outcome::Timeout { winner: ChessColor::White }
+    );
+    assert_eq!(
+        timeout_outcome(&board("4k3/4p3/8/8/8/8/8/4KB2 w - - 0 1"), ChessColor::Black),
+        Outcome::Timeout { winner: ChessColor::White }
+    );
+    // ...but not against a bare king, or with bishops all on one colour
+    assert_eq!(
+        timeout_outcome(&board("4k3/8/8/8/8/8/8/4K1N1 w - - 0 1"), ChessColor::Black),
+        Outcome::Draw(DrawReason::TimeoutVsInsufficientMaterial)
+    );
+    assert_eq!(
+        timeout_outcome(&board("2b1k3/8/8/8/8/8/8/4KB2 w - - 0 1"), ChessColor::Black),
+        Outcome::Draw(DrawReason::TimeoutVsInsufficientMaterial)
+    );
+    assert_eq!(
+        timeout_outcome(&board("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1"), ChessColor::Black),
+        Outcome::Timeout { winner: ChessColor::White }
+    );
+    assert_eq!(
+        timeout_outcome(&board("4k3/8/8/8/8/8/8/3BKB2 w - - 0 1"), ChessColor::Black),
+        Outcome::Draw(DrawReason::TimeoutVsInsufficientMaterial)
+    );
+    assert_eq!(
+        timeout_outcome(&board("4k3/8/8/8/8/8/r7/4K1N1 w - - 0 1"), ChessColor::White),
+        Outcome::Timeout { winner: ChessColor::Black }
+    );
+    assert_eq!(
+        timeout_outcome(&board("4k3/8/8/8/8/8/8/2B1KN2 w - - 0 1"), ChessColor::Black),
+        Outcome::Timeout { winner: ChessColor::White }
+    );
+}
+
+#[test]
+fn engine_budget_comes_from_its_clock() {
+    let hard = Difficulty::Hard.profile();
+    let plenty = hard.clock_limits(300_000, 2_000);
+    let short = hard.clock_limits(5_000, 0);
+    assert!(short.time_ms < plenty.time_ms);
+    assert!(short.time_ms.unwrap() < 5_000);
+
+    let easy = Difficulty::Easy.profile();
+    assert_eq!(easy.clock_limits(300_000, 0).depth, easy.limits().depth);
+}
@@ -0,0 +1,56 @@
+use chess::{ChessMove, Square};
+use chess_ai_app::engine::difficulty::{pick_move, Difficulty};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[test]
+fn profiles_get_monotonically_stronger() {
+    for pair in Difficulty::ALL.windows(2) {
+        let (weaker, stronger) = (pair[0].profile(), pair[1].profile());
+        assert!(stronger.depth >= weaker.depth, "{:?} -> {:?}", pair[0], pair[1]);
+        assert!(stronger.time_ms >= weaker.time_ms, "{:?} -> {:?}", pair[0], pair[1]);
+        assert!(stronger.eval_noise <= weaker.eval_noise, "{:?} -> {:?}", pair[0], pair[1]);
+        assert!(stronger.blunder_chance <= weaker.blunder_chance, "{:?} -> {:?}", pair[0], pair[1]);
+        assert_ne!(stronger, weaker);
+    }
+}
+
+#[test]
+fn hard_plays_its_best() {
+    let hard = Difficulty::Hard.profile();
+    assert_eq!(hard.eval_noise, 0);
+    assert_eq!(hard.blunder_chance, 0.0);
+
+    let best = ChessMove::new(Square::E2, Square::E4, None);
+    let scored = [
+        (ChessMove::new(Square::A2, Square::A3, None), -20),
+        (best, 35),
+        (ChessMove::new(Square::G1, Square::F3, None), 30),
+    ];
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..100 {
+        assert_eq!(pick_move(&scored, &hard, &mut rng), Some(best));
+    }
+}
+
+#[test]
+fn easy_sometimes_misses_the_best_move() {
+    let easy = Difficulty::Easy.profile();
+    let best = ChessMove::new(Square::D1, Square::H5, None);
+    let scored = [
+        (best, 900),
+        (ChessMove::new(Square::A2, Square::A3, None), 0),
+        (ChessMove::new(Square::B2, Square::B3, None), 0),
+    ];
+    let mut rng = StdRng::seed_from_u64(7);
+    let misses = (0..200)
+        .filter(|_| pick_move(&scored, &easy, &mut rng) != Some(best))
+        .count();
+    assert!(misses > 0 && misses < 200, "misses: {misses}");
+}
+
+#[test]
+fn no_moves_means_no_pick() {
+    let mut rng = StdRng::seed_from_u64(1);
+    assert_eq!(pick_move(&[], &Difficulty::Medium.profile(), &mut rng), None);
+}
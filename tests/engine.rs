@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove, Piece, Square};
+use chess_ai_app::engine::{Engine, GameRecord, SearchLimits};
+
+fn mv(text: &str) -> ChessMove {
+    ChessMove::from_str(text).unwrap()
+}
+
+#[test]
+fn engine_searches_the_position_it_was_given() {
+    let mut engine = Engine::with_hash_mb(1);
+    let mate = Board::from_str("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    engine.set_position(mate);
+    assert_eq!(engine.position(), &mate);
+
+    let mut iterations = Vec::new();
+    let result = engine.search_with(SearchLimits::depth(3), None, |r| iterations.push(r.depth));
+    assert_eq!(result.best_move, Some(mv("a1a8")));
+    assert_eq!(iterations.last().copied(), Some(result.depth));
+
+    engine.new_game();
+    assert_eq!(engine.position(), &Board::default());
+    assert!(engine.search(SearchLimits::depth(2)).best_move.is_some());
+}
+
+#[test]
+fn record_replays_from_its_start_position() {
+    let mut record = GameRecord::default();
+    for text in ["e2e4", "d7d5", "e4d5", "d8d5"] {
+        record.push(mv(text));
+    }
+
+    assert_eq!(record.len(), 4);
+    assert_eq!(record.board_at(0), Board::default());
+    assert_eq!(record.board_at(1), Board::default().make_move_new(mv("e2e4")));
+    assert_eq!(record.last(), Some(mv("d8d5")));
+    assert_eq!(record.captured(), (vec![Piece::Pawn], vec![Piece::Pawn]));
+
+    record.pop();
+    assert_eq!(record.board().piece_on(Square::D5), Some(Piece::Pawn));
+    record.clear();
+    assert!(record.is_empty());
+    assert_eq!(record.board(), Board::default());
+}
+
+#[test]
+fn record_counts_en_passant_captures() {
+    let mut record = GameRecord::default();
+    for text in ["e2e4", "a7a6", "e4e5", "d7d5", "e5d6"] {
+        record.push(mv(text));
+    }
+    assert_eq!(record.captured(), (vec![], vec![Piece::Pawn]));
+}
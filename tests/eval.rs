@@ -0,0 +1,94 @@
+use std::str::FromStr;
+
+use chess::Board;
+use chess_ai_app::engine::eval::evaluate;
+
+fn eval_fen(fen: &str) -> i32 {
+    evaluate(&Board::from_str(fen).expect("valid FEN"))
+}
+
+/// Swap colours and mirror ranks so the evaluation should flip sign.
+fn mirror_fen(fen: &str) -> String {
+    let mut parts = fen.split_whitespace();
+    let placement: Vec<String> = parts
+        .next()
+        .unwrap()
+        .split('/')
+        .rev()
+        .map(|rank| {
+            rank.chars()
+                .map(|c| if c.is_ascii_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() })
+                .collect()
+        })
+        .collect();
+    let side = if parts.next() == Some("w") { "b" } else { "w" };
+    format!("{} {} - - 0 1", placement.join("/"), side)
+}
+
+#[test]
+fn start_position_is_balanced() {
+    assert_eq!(evaluate(&Board::default()), 0);
+}
+
+#[test]
+fn evaluation_is_colour_symmetric() {
+    let fens = [
+        "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "6k1/5ppp/8/8/8/8/1P3PPP/6K1 w - - 0 1",
+    ];
+    for fen in fens {
+        assert_eq!(eval_fen(fen), -eval_fen(&mirror_fen(fen)), "{fen}");
+    }
+}
+
+#[test]
+fn counts_material() {
+    // White is a queen up.
+    let up_queen = eval_fen("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert!(up_queen > 700, "queen up scored {up_queen}");
+
+    // Black is a knight up.
+    let down_knight = eval_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR w KQkq - 0 1");
+    assert!(down_knight < -200, "knight down scored {down_knight}");
+}
+
+#[test]
+fn prefers_centralised_knights() {
+    let rim = eval_fen("4k3/8/8/8/8/8/8/N3K3 w - - 0 1");
+    let centre = eval_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1");
+    assert!(centre > rim, "centre {centre} vs rim {rim}");
+}
+
+#[test]
+fn penalises_doubled_and_isolated_pawns() {
+    let healthy = eval_fen("4k3/8/8/8/8/8/3PP3/4K3 w - - 0 1");
+    let doubled = eval_fen("4k3/8/8/8/8/3P4/3P4/4K3 w - - 0 1");
+    assert!(healthy > doubled, "healthy {healthy} vs doubled {doubled}");
+}
+
+#[test]
+fn rewards_advanced_passed_pawns() {
+    let blocked = eval_fen("4k3/8/3p4/8/3P4/8/8/4K3 w - - 0 1");
+    let passed = eval_fen("4k3/8/p7/8/3P4/8/8/4K3 w - - 0 1");
+    assert!(passed > blocked, "passed {passed} vs blocked {blocked}");
+
+    let far = eval_fen("4k3/8/8/8/8/8/P7/4K3 w - - 0 1");
+    let near = eval_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+    assert!(near > far + 50, "seventh rank {near} vs second rank {far}");
+}
+
+#[test]
+fn king_centralises_in_the_endgame() {
+    let corner = eval_fen("4k3/8/8/8/8/8/8/R6K w - - 0 1");
+    let centre = eval_fen("4k3/8/8/8/3K4/8/8/R7 w - - 0 1");
+    assert!(centre > corner, "centre {centre} vs corner {corner}");
+}
+
+#[test]
+fn values_an_intact_pawn_shield() {
+    let sheltered = eval_fen("r2q1rk1/ppp2ppp/8/8/8/8/PPP2PPP/R2Q1RK1 w - - 0 1");
+    let exposed = eval_fen("r2q1rk1/ppp2ppp/8/8/8/5P1P/PPP3P1/R2Q1RK1 w - - 0 1");
+    assert!(sheltered > exposed, "sheltered {sheltered} vs exposed {exposed}");
+}
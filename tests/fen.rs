@@ -0,0 +1,52 @@
+use chess::{Board, Color as ChessColor};
+use chess_ai_app::engine::fen::parse_fen;
+use chess_ai_app::engine::GameRecord;
+
+fn error(fen: &str) -> String {
+    parse_fen(fen).expect_err(fen).to_string()
+}
+
+#[test]
+fn accepts_full_and_abbreviated_fen() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(parse_fen(start), Ok(Board::default()));
+    assert_eq!(parse_fen("  rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -  "), Ok(Board::default()));
+
+    let bare = parse_fen("4k3/8/8/8/8/8/4P3/4K3").unwrap();
+    assert_eq!(bare.side_to_move(), ChessColor::White);
+    let black = parse_fen("4k3/8/8/8/8/8/4P3/4K3 b").unwrap();
+    assert_eq!(black.side_to_move(), ChessColor::Black);
+}
+
+#[test]
+fn explains_what_is_wrong() {
+    assert_eq!(error(""), "FEN is empty");
+    assert!(error("4k3/8/8/8/8/8/4K3 w - - 0 1").contains("8 ranks"));
+    assert!(error("4k3/8/8/8/8/8/4P4/4K3 w - - 0 1").contains("rank 2 has 9 squares"));
+    assert!(error("4k3/8/8/8/8/8/4X3/4K3 w - - 0 1").contains("unknown piece 'X'"));
+    assert!(error("8/8/8/8/8/8/8/4K3 w - - 0 1").contains("Black needs exactly one king"));
+    assert!(error("4k3/8/8/8/8/8/8/4K3 x - - 0 1").contains("side to move"));
+    assert!(error("4k3/8/8/8/8/8/8/4K3 w KX - 0 1").contains("castling"));
+    assert!(error("4k3/8/8/8/8/8/8/4K3 w - e5 0 1").contains("en passant"));
+    assert!(error("4k3/8/8/8/8/8/8/4K3 w - - x 1").contains("halfmove"));
+    assert!(error("P3k3/8/8/8/8/8/8/4K3 w - - 0 1").contains("first or last rank"));
+    // Black is in check with White to move
+    assert!(error("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1").contains("illegal position"));
+}
+
+#[test]
+fn records_replay_from_a_custom_start() {
+    let start = parse_fen("4k3/8/8/8/8/8/2p1K3/7R b - - 0 1").unwrap();
+    let mut record = GameRecord::new(start);
+    for mv in ["c2c1q", "h1c1", "e8d7"] {
+        record.push(mv.parse().unwrap());
+    }
+    // The queen was promoted, not part of the start position, so it is the
+    // only capture.
+    assert_eq!(record.captured(), (vec![], vec![chess::Piece::Queen]));
+
+    record.pop();
+    record.pop();
+    assert_eq!(record.board(), start.make_move_new("c2c1q".parse().unwrap()));
+    assert_eq!(record.board_at(0), start);
+}
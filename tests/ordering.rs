@@ -0,0 +1,58 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove, MoveGen};
+use chess_ai_app::engine::bench::run_bench;
+use chess_ai_app::engine::ordering::{mvv_lva, order_captures, MoveOrderer};
+use chess_ai_app::engine::SearchFeatures;
+
+fn board(fen: &str) -> Board {
+    Board::from_str(fen).expect("valid FEN")
+}
+
+fn mv(text: &str) -> ChessMove {
+    ChessMove::from_str(text).unwrap()
+}
+
+fn legal(board: &Board) -> Vec<ChessMove> {
+    MoveGen::new_legal(board).collect()
+}
+
+#[test]
+fn captures_take_the_biggest_piece_with_the_smallest() {
+    // The d4 pawn and the c3 knight can both take the queen on e5; the
+    // knight can also take a pawn on d5
+    let pos = board("4k3/8/8/3pq3/3P4/2N5/8/4K3 w - - 0 1");
+    assert!(mvv_lva(&pos, mv("d4e5")) > mvv_lva(&pos, mv("c3e4")));
+    assert!(mvv_lva(&pos, mv("d4e5")) > mvv_lva(&pos, mv("c3d5")));
+
+    let captures: Vec<ChessMove> = legal(&pos).into_iter().filter(|&m| pos.piece_on(m.get_dest()).is_some()).collect();
+    assert_eq!(order_captures(&pos, captures)[0], mv("d4e5"));
+
+    // En passant lands on an empty square but is still a capture
+    let ep = board("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 2");
+    assert!(mvv_lva(&ep, mv("d5e6")) > 0);
+}
+
+#[test]
+fn hash_move_then_captures_then_killers_then_history() {
+    let pos = board("4k3/8/8/3p4/4P3/8/8/R3K3 w - - 0 1");
+    let mut orderer = MoveOrderer::new();
+    orderer.record_cutoff(&pos, mv("a1a7"), 3, 2);
+    orderer.record_cutoff(&pos, mv("a1a5"), 3, 2);
+    // A quiet move with a history score from another ply, but no killer slot here
+    orderer.record_cutoff(&pos, mv("e1f2"), 6, 4);
+
+    let order = orderer.order(&pos, legal(&pos), Some(mv("a1a2")), 2);
+    assert_eq!(&order[..5], [mv("a1a2"), mv("e4d5"), mv("a1a5"), mv("a1a7"), mv("e1f2")]);
+
+    // Without killers at this ply, history alone ranks the quiet moves
+    let order = orderer.order(&pos, legal(&pos), None, 0);
+    assert_eq!(&order[..2], [mv("e4d5"), mv("e1f2")]);
+}
+
+#[test]
+fn bench_node_counts_are_reproducible() {
+    let first = run_bench(3, SearchFeatures::ALL);
+    assert!(first.nodes > 0);
+    assert_eq!(run_bench(3, SearchFeatures::ALL).nodes, first.nodes);
+}
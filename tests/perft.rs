@@ -0,0 +1,79 @@
+//! Standard perft positions from the Chess Programming Wiki.
+
+use std::str::FromStr;
+
+use chess::{Board, ChessMove};
+use chess_ai_app::engine::perft::{divide, perft};
+use chess_ai_app::engine::GameRecord;
+
+const STARTPOS: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+fn check(fen: &str, expected: &[u64]) {
+    let board = Board::from_str(fen).expect("valid FEN");
+    for (depth, &nodes) in (1..).zip(expected) {
+        assert_eq!(perft(&board, depth), nodes, "{fen} at depth {depth}");
+    }
+}
+
+#[test]
+fn startpos() {
+    check(STARTPOS, &[20, 400, 8_902, 197_281, 4_865_609]);
+}
+
+#[test]
+fn kiwipete() {
+    check(KIWIPETE, &[48, 2_039, 97_862, 4_085_603]);
+}
+
+#[test]
+fn position_3() {
+    check(POSITION_3, &[14, 191, 2_812, 43_238, 674_624]);
+}
+
+#[test]
+fn position_4() {
+    check(POSITION_4, &[6, 264, 9_467, 422_333]);
+}
+
+#[test]
+fn position_5() {
+    check(POSITION_5, &[44, 1_486, 62_379, 2_103_487]);
+}
+
+#[test]
+fn position_6() {
+    check(POSITION_6, &[46, 2_079, 89_890, 3_894_594]);
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    let board = Board::from_str(KIWIPETE).unwrap();
+    let split = divide(&board, 3);
+    assert_eq!(split.len(), 48);
+    assert_eq!(split.iter().map(|&(_, n)| n).sum::<u64>(), perft(&board, 3));
+}
+
+#[test]
+fn replayed_games_match_their_fen() {
+    // Same kind of moves the GUI records: promotions with capture (one an
+    // underpromotion), castling rights lost, then replayed as undo does.
+    let start = Board::from_str("r3k3/1P6/8/8/8/8/6p1/4K2R w K - 0 1").unwrap();
+    let mut record = GameRecord::new(start);
+    for text in ["b7a8q", "e8e7", "e1f2", "g2h1n", "f2g1"] {
+        let mv = ChessMove::from_str(text).unwrap();
+        assert!(record.board().legal(mv), "{text}");
+        record.push(mv);
+    }
+
+    let replayed = record.board();
+    let from_fen = Board::from_str(&replayed.to_string()).unwrap();
+    assert_eq!(replayed, from_fen);
+    for depth in 1..=3 {
+        assert_eq!(perft(&replayed, depth), perft(&from_fen, depth));
+    }
+}
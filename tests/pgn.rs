@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove};
+use chess::Color as ChessColor;
+use chess_ai_app::engine::pgn::{export, parse, today, GameResult, PgnTags};
+use chess_ai_app::engine::GameRecord;
+
+fn record(start: Board, moves: &[&str]) -> GameRecord {
+    let mut record = GameRecord::new(start);
+    for text in moves {
+        record.push(ChessMove::from_str(text).unwrap());
+    }
+    record
+}
+
+fn tags() -> PgnTags {
+    PgnTags { date: "2024.01.02".to_string(), ..PgnTags::new("Player", "Chess AI (Hard)") }
+}
+
+#[test]
+fn writes_the_seven_tag_roster_and_numbered_moves() {
+    let game = record(Board::default(), &["f2f3", "e7e5", "g2g4", "d8h4"]);
+    let mut tags = tags();
+    tags.result = GameResult::from_board(&game.board());
+    assert_eq!(tags.result, GameResult::BlackWins);
+
+    let pgn = export(&game, &tags);
+    let expected = "[Event \"Casual game\"]\n\
+                    [Site \"Chess AI\"]\n\
+                    [Date \"2024.01.02\"]\n\
+                    [Round \"-\"]\n\
+                    [White \"Player\"]\n\
+                    [Black \"Chess AI (Hard)\"]\n\
+                    [Result \"0-1\"]\n\
+                    \n\
+                    1. f3 e5 2. g4 Qh4# 0-1\n";
+    assert_eq!(pgn, expected);
+}
+
+#[test]
+fn records_a_custom_start_position() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1";
+    let game = record(Board::from_str(fen).unwrap(), &["e8d7", "e2e4"]);
+    let pgn = export(&game, &tags());
+    assert!(pgn.contains("[SetUp \"1\"]\n"));
+    assert!(pgn.contains(&format!("[FEN \"{}\"]\n", Board::from_str(fen).unwrap())));
+    assert!(pgn.ends_with("\n1... Kd7 2. e4 *\n"), "{pgn}");
+}
+
+#[test]
+fn keeps_the_starting_move_number() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 3 30";
+    let mut game = GameRecord::new(Board::from_str(fen).unwrap()).with_halfmove_clock(3).with_fullmove_number(30);
+    for text in ["e8d7", "e2e4"] {
+        game.push(ChessMove::from_str(text).unwrap());
+    }
+    let pgn = export(&game, &tags());
+    assert!(pgn.contains(&format!("[FEN \"{fen}\"]\n")), "{pgn}");
+    assert!(pgn.ends_with("\n30... Kd7 31. e4 *\n"), "{pgn}");
+
+    let games = parse(&pgn).unwrap();
+    assert_eq!(games[0].record, game);
+    assert_eq!(games[0].record.start_fullmove_number(), 30);
+}
+
+#[test]
+fn wraps_long_movetext() {
+    let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    let moves: Vec<&str> = shuffle.iter().cycle().take(60).copied().collect();
+    let pgn = export(&record(Board::default(), &moves), &tags());
+    let movetext = pgn.split("\n\n").nth(1).unwrap();
+    assert!(movetext.lines().count() > 1);
+    assert!(movetext.lines().all(|line| line.len() < 80));
+    assert!(movetext.contains("30. Ng1 Ng8 *"));
+}
+
+#[test]
+fn today_is_a_pgn_date() {
+    let date = today();
+    let parts: Vec<&str> = date.split('.').collect();
+    assert_eq!(parts.len(), 3, "{date}");
+    assert!(parts[0].parse::<u32>().unwrap() >= 2024);
+    assert!((1..=12).contains(&parts[1].parse::<u32>().unwrap()));
+    assert!((1..=31).contains(&parts[2].parse::<u32>().unwrap()));
+}
+
+#[test]
+fn import_round_trips_export() {
+    let game = record(Board::default(), &["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6", "e1g1"]);
+    let text = export(&game, &tags());
+    let games = parse(&text).unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].record, game);
+    assert_eq!(games[0].tag("Black"), Some("Chess AI (Hard)"));
+    assert_eq!(games[0].result, GameResult::Ongoing);
+}
+
+#[test]
+fn reads_several_games_and_skips_annotations() {
+    let text = r#"[Event "One"]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Nf3 $1 (2. f4 exf4 (2... d5)) Nc6; a comment
+3.Bb5 a6 1-0
+
+[Event "Two"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 40"]
+
+40... Kd7 41. e4 1/2-1/2
+"#;
+    let games = parse(text).unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].record.len(), 6);
+    assert_eq!(games[0].result, GameResult::WhiteWins);
+    assert_eq!(games[1].tag("Event"), Some("Two"));
+    assert_eq!(games[1].record.len(), 2);
+    assert_eq!(games[1].result, GameResult::Draw);
+}
+
+#[test]
+fn reports_the_offending_move() {
+    let err = parse("[Event \"Bad\"]\n\n1. e4 e5 2. Nf3 Ke6 *").unwrap_err();
+    assert_eq!(err.game, 1);
+    assert_eq!(err.move_number, Some((2, ChessColor::Black)));
+    assert_eq!(err.to_string(), "game 1, move 2... Ke6: illegal move");
+
+    let err = parse("1. e4 e5 *\n\n1. d4 d5 2. Qd9 *").unwrap_err();
+    assert_eq!(err.game, 2);
+    assert_eq!(err.move_number, Some((2, ChessColor::White)));
+}
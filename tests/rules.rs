@@ -0,0 +1,103 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove, Color as ChessColor};
+use chess_ai_app::engine::rules::{is_dead_position, DrawReason, Outcome};
+use chess_ai_app::engine::{Engine, GameRecord, SearchLimits};
+
+fn mv(text: &str) -> ChessMove {
+    ChessMove::from_str(text).unwrap()
+}
+
+fn play(start: &str, moves: &[&str]) -> GameRecord {
+    let mut record = GameRecord::new(Board::from_str(start).unwrap());
+    for text in moves {
+        record.push(mv(text));
+    }
+    record
+}
+
+fn dead(fen: &str) -> bool {
+    is_dead_position(&Board::from_str(fen).unwrap())
+}
+
+const KNIGHT_DANCE: [&str; 4] = ["g1f3", "g8f6", "f3g1", "f6g8"];
+
+#[test]
+fn threefold_and_fivefold_repetition() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    let twice = play(start, &KNIGHT_DANCE);
+    assert_eq!(twice.repetitions(), 2);
+    assert_eq!(twice.outcome(), None);
+
+    let moves: Vec<&str> = KNIGHT_DANCE.iter().cycle().take(8).copied().collect();
+    let thrice = play(start, &moves);
+    assert_eq!(thrice.repetitions(), 3);
+    assert_eq!(thrice.outcome(), Some(Outcome::Draw(DrawReason::ThreefoldRepetition)));
+
+    let moves: Vec<&str> = KNIGHT_DANCE.iter().cycle().take(16).copied().collect();
+    assert_eq!(play(start, &moves).outcome(), Some(Outcome::Draw(DrawReason::FivefoldRepetition)));
+}
+
+#[test]
+fn halfmove_clock_and_move_count_rules() {
+    let record = play(Board::default().to_string().as_str(), &["g1f3", "e7e5", "f3e5", "b8c6"]);
+    assert_eq!(record.halfmove_clock(), 1, "the capture resets the clock");
+
+    // Rooks shuffle from a clock of 98: the 100th halfmove is a draw.
+    let start = Board::from_str("r3k3/8/8/8/8/8/8/R3K3 w - - 98 80").unwrap();
+    let mut record = GameRecord::new(start).with_halfmove_clock(98);
+    record.push(mv("a1b1"));
+    assert_eq!(record.outcome(), None);
+    record.push(mv("a8b8"));
+    assert_eq!(record.outcome(), Some(Outcome::Draw(DrawReason::FiftyMoveRule)));
+
+    let mut record = GameRecord::new(start).with_halfmove_clock(149);
+    record.push(mv("a1b1"));
+    assert_eq!(record.outcome(), Some(Outcome::Draw(DrawReason::SeventyFiveMoveRule)));
+    record.pop();
+    assert_eq!(record.halfmove_clock(), 149);
+}
+
+#[test]
+fn mate_takes_precedence_over_the_clock() {
+    let start = Board::from_str("6k1/5ppp/8/8/8/8/8/R5K1 w - - 149 100").unwrap();
+    let mut record = GameRecord::new(start).with_halfmove_clock(149);
+    record.push(mv("a1a8"));
+    assert_eq!(record.outcome(), Some(Outcome::Checkmate { winner: ChessColor::White }));
+}
+
+#[test]
+fn recognises_dead_positions() {
+    assert!(dead("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+    assert!(dead("4k3/8/8/8/8/8/8/4KN2 w - - 0 1"));
+    assert!(dead("4k3/8/8/8/8/8/8/4KB2 w - - 0 1"));
+    // Bishops on the same colour (c8 and f1 are both light).
+    assert!(dead("2b1k3/8/8/8/8/8/8/4KB2 w - - 0 1"));
+    assert!(!dead("3bk3/8/8/8/8/8/8/4KB2 w - - 0 1"));
+    assert!(!dead("4k3/8/8/8/8/8/8/3NKN2 w - - 0 1"));
+    assert!(!dead("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"));
+
+    let record = play("4k3/8/8/8/8/8/3r4/4K3 w - - 0 1", &["e1d2"]);
+    assert_eq!(record.outcome(), Some(Outcome::Draw(DrawReason::DeadPosition)));
+    assert_eq!(record.outcome().unwrap().to_string(), "Draw by insufficient material");
+}
+
+#[test]
+fn search_scores_a_repetition_as_a_draw() {
+    let start = "4k3/8/8/8/8/8/8/3QK3 w - - 0 1";
+    let limits = SearchLimits { exact_root_scores: true, ..SearchLimits::depth(1) };
+    let score_of = |engine: &mut Engine, text: &str| {
+        let result = engine.search(limits);
+        result.root_scores.iter().find(|(m, _)| *m == mv(text)).unwrap().1
+    };
+
+    let mut engine = Engine::with_hash_mb(1);
+    engine.set_position(Board::from_str(start).unwrap());
+    assert!(score_of(&mut engine, "d1d2") > 500);
+
+    // After Qd2 Kf8 Qd1 Ke8, playing Qd2 again repeats a position.
+    engine.new_game();
+    engine.set_game(&play(start, &["d1d2", "e8f8", "d2d1", "f8e8"]));
+    assert_eq!(score_of(&mut engine, "d1d2"), 0);
+    assert!(score_of(&mut engine, "d1d3") > 500);
+}
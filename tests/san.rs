@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove};
+use chess_ai_app::engine::san::{move_pairs, parse_san, to_san, MovePair, SanError};
+use chess_ai_app::engine::GameRecord;
+
+fn san(fen: &str, uci: &str) -> String {
+    let board = Board::from_str(fen).expect("valid FEN");
+    let mv = ChessMove::from_str(uci).expect("valid move");
+    assert!(board.legal(mv), "{uci} is illegal in {fen}");
+    to_san(&board, mv)
+}
+
+#[test]
+fn pieces_and_pawns() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(san(start, "e2e4"), "e4");
+    assert_eq!(san(start, "g1f3"), "Nf3");
+    assert_eq!(san("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2", "e4d5"), "exd5");
+}
+
+#[test]
+fn disambiguates_by_file_then_rank() {
+    // Knights on b1 and f3 can both reach d2.
+    assert_eq!(san("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1", "b1d2"), "Nbd2");
+    // Rooks on a1 and a5 share a file.
+    assert_eq!(san("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1", "a1a3"), "R1a3");
+    // Three queens need the full square.
+    assert_eq!(san("2k5/8/8/8/Q6Q/8/8/Q3K3 w - - 0 1", "a4d4"), "Qa4d4");
+    assert_eq!(san("2k5/8/8/8/Q6Q/8/8/Q3K3 w - - 0 1", "h4e4"), "Qhe4");
+}
+
+#[test]
+fn castling() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    assert_eq!(san(fen, "e1g1"), "O-O");
+    assert_eq!(san(fen, "e1c1"), "O-O-O");
+}
+
+#[test]
+fn promotion_and_en_passant() {
+    assert_eq!(san("8/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7e8q"), "e8=Q");
+    assert_eq!(san("3r4/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7d8n"), "exd8=N");
+    assert_eq!(san("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1", "d5e6"), "dxe6");
+}
+
+#[test]
+fn check_and_mate_suffixes() {
+    assert_eq!(san("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1a8"), "Ra8+");
+    // Fool's mate
+    assert_eq!(san("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq g3 0 2", "d8h4"), "Qh4#");
+}
+
+#[test]
+fn parses_what_it_writes() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k3/1P6/8/8/8/8/6p1/4K2R b K - 0 1",
+        "2k5/8/8/8/Q6Q/8/8/Q3K3 w - - 0 1",
+    ];
+    for fen in fens {
+        let board = Board::from_str(fen).unwrap();
+        for mv in chess::MoveGen::new_legal(&board) {
+            let text = to_san(&board, mv);
+            assert_eq!(parse_san(&board, &text), Ok(mv), "{text} in {fen}");
+        }
+    }
+}
+
+#[test]
+fn parsing_is_lenient_about_decoration() {
+    let board = Board::from_str("r3k2r/8/8/8/8/8/4P3/R3K2R w KQkq - 0 1").unwrap();
+    assert_eq!(parse_san(&board, "0-0").unwrap().to_string(), "e1g1");
+    assert_eq!(parse_san(&board, "O-O-O+").unwrap().to_string(), "e1c1");
+    assert_eq!(parse_san(&board, "e4!?").unwrap().to_string(), "e2e4");
+    assert!(parse_san(&board, "Rah1").is_err());
+    // Redundant disambiguation is fine as long as it is right.
+    assert_eq!(parse_san(&board, "Ra1b1").unwrap().to_string(), "a1b1");
+
+    let promo = Board::from_str("8/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+    assert_eq!(parse_san(&promo, "e8Q").unwrap().to_string(), "e7e8q");
+}
+
+#[test]
+fn reports_why_a_move_does_not_fit() {
+    let board = Board::default();
+    assert_eq!(parse_san(&board, "e5"), Err(SanError::Illegal));
+    assert_eq!(parse_san(&board, "hello"), Err(SanError::Malformed));
+    let knights = Board::from_str("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+    assert_eq!(parse_san(&knights, "Nd2"), Err(SanError::Ambiguous));
+}
+
+fn pair(number: u32, white: Option<&str>, black: Option<&str>) -> MovePair {
+    MovePair { number, white: white.map(str::to_string), black: black.map(str::to_string) }
+}
+
+#[test]
+fn move_list_pairs_up_moves() {
+    let mut record = GameRecord::default();
+    for uci in ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5"] {
+        record.push(ChessMove::from_str(uci).unwrap());
+    }
+    assert_eq!(
+        move_pairs(&record),
+        [
+            pair(1, Some("e4"), Some("e5")),
+            pair(2, Some("Nf3"), Some("Nc6")),
+            pair(3, Some("Bb5"), None),
+        ]
+    );
+
+    let mut record = GameRecord::new(Board::from_str("4k3/8/8/8/8/8/4P3/4K2R b K - 0 1").unwrap());
+    for uci in ["e8d7", "e1g1", "d7d6"] {
+        record.push(ChessMove::from_str(uci).unwrap());
+    }
+    assert_eq!(
+        move_pairs(&record),
+        [pair(1, None, Some("Kd7")), pair(2, Some("O-O"), Some("Kd6"))]
+    );
+
+    let mut record = GameRecord::new(*record.start()).with_fullmove_number(30);
+    record.push(ChessMove::from_str("e8d7").unwrap());
+    assert_eq!(move_pairs(&record), [pair(30, None, Some("Kd7"))]);
+    assert!(move_pairs(&GameRecord::default()).is_empty());
+}
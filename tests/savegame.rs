@@ -0,0 +1,77 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chess::{Board, ChessMove, Color as ChessColor};
+use chess_ai_app::engine::clock::{ChessClock, TimeControl};
+use chess_ai_app::engine::savegame::SavedGame;
+use chess_ai_app::engine::{Difficulty, GameRecord};
+
+fn record(start: &str, moves: &[&str], halfmove_clock: u32) -> GameRecord {
+    let mut record = GameRecord::new(Board::from_str(start).unwrap()).with_halfmove_clock(halfmove_clock);
+    for text in moves {
+        record.push(ChessMove::from_str(text).unwrap());
+    }
+    record
+}
+
+#[test]
+fn saved_games_round_trip() {
+    let mut clock = ChessClock::new(TimeControl::from_str("5 b3").unwrap());
+    clock.set_remaining(ChessColor::White, Duration::from_millis(241_300));
+    clock.set_remaining(ChessColor::Black, Duration::from_millis(12_050));
+    let saved = SavedGame {
+        record: record("4k3/8/8/8/8/8/4P3/4K2R b K - 7 40", &["e8d7", "e1g1", "d7c6"], 7),
+        difficulty: Difficulty::Hard,
+        engine_plays: [false, true],
+        analysis: false,
+        clock: Some(clock),
+    };
+
+    let text = saved.to_pgn();
+    assert!(text.contains("[BlackType \"program\"]"), "{text}");
+    assert!(text.contains("[Clock \"5 b3\"]"), "{text}");
+    let loaded = SavedGame::from_pgn(&text).unwrap();
+    assert_eq!(loaded, saved);
+    assert_eq!(loaded.record.halfmove_clock(), saved.record.halfmove_clock());
+}
+
+#[test]
+fn untimed_games_and_plain_pgn_load() {
+    let saved = SavedGame {
+        record: record("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &["e2e4", "c7c5"], 0),
+        difficulty: Difficulty::Easy,
+        engine_plays: [true, true],
+        analysis: false,
+        clock: None,
+    };
+    assert_eq!(SavedGame::from_pgn(&saved.to_pgn()), Ok(saved));
+
+    let plain = SavedGame::from_pgn("[White \"A\"]\n[Black \"B\"]\n\n1. d4 d5 *\n").unwrap();
+    assert_eq!(plain.record.len(), 2);
+    assert_eq!(plain.engine_plays, [false, false]);
+    assert!(!plain.analysis);
+    assert_eq!(plain.difficulty, Difficulty::Medium);
+    assert_eq!(plain.clock, None);
+
+    assert!(SavedGame::from_pgn("[Difficulty \"Impossible\"]\n\n*\n").is_err());
+    assert!(SavedGame::from_pgn("").is_err());
+}
+
+#[test]
+fn analysis_mode_is_kept() {
+    let saved = SavedGame {
+        record: record("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &["d2d4"], 0),
+        difficulty: Difficulty::Medium,
+        engine_plays: [false, false],
+        analysis: true,
+        clock: None,
+    };
+    let text = saved.to_pgn();
+    assert!(text.contains("[Mode \"analysis\"]"), "{text}");
+    assert_eq!(SavedGame::from_pgn(&text), Ok(saved.clone()));
+
+    // A hotseat game between the same two humans stays one
+    let hotseat = SavedGame { analysis: false, ..saved };
+    assert!(!hotseat.to_pgn().contains("[Mode "));
+    assert_eq!(SavedGame::from_pgn(&hotseat.to_pgn()), Ok(hotseat));
+}
@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove, Square};
+use chess_ai_app::engine::bench::{run_bench, run_tactics};
+use chess_ai_app::engine::search::{choose_best_move_ab, mate_in, SearchFeatures, SearchLimits, SearchResult, MAX_SEARCH_DEPTH};
+use chess_ai_app::engine::tt::TranspositionTable;
+
+fn board(fen: &str) -> Board {
+    Board::from_str(fen).expect("valid FEN")
+}
+
+fn search(board: &Board, limits: SearchLimits) -> SearchResult {
+    choose_best_move_ab(board, limits, &mut TranspositionTable::new(1))
+}
+
+#[test]
+fn finds_back_rank_mate() {
+    let result = search(&board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"), SearchLimits::depth(3));
+    assert_eq!(result.best_move, Some(ChessMove::new(Square::A1, Square::A8, None)));
+    assert_eq!(mate_in(result.score), Some(1));
+}
+
+#[test]
+fn pv_starts_with_best_move_and_is_legal() {
+    let start = board("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3");
+    let result = search(&start, SearchLimits::depth(4));
+    assert_eq!(result.depth, 4);
+    assert_eq!(result.pv.first().copied(), result.best_move);
+
+    let mut pos = start;
+    for &mv in &result.pv {
+        assert!(pos.legal(mv), "illegal PV move {mv}");
+        pos = pos.make_move_new(mv);
+    }
+}
+
+#[test]
+fn respects_time_budget() {
+    let result = search(&Board::default(), SearchLimits::time_ms(200));
+    assert!(result.best_move.is_some());
+    // Stopped by the clock rather than by running out of depth, and not far
+    // past it, though a busy machine may be slow to check the time
+    assert!(result.depth < MAX_SEARCH_DEPTH);
+    assert!(result.elapsed_ms < 5 * 200, "took {} ms", result.elapsed_ms);
+}
+
+#[test]
+fn respects_node_budget() {
+    let limits = SearchLimits { nodes: Some(5_000), ..Default::default() };
+    let result = search(&Board::default(), limits);
+    assert!(result.best_move.is_some());
+    assert!(result.nodes <= 5_000, "searched {} nodes", result.nodes);
+}
+
+#[test]
+fn no_move_when_game_is_over() {
+    let mated = board("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1");
+    assert_eq!(search(&mated, SearchLimits::depth(2)).best_move, None);
+}
+
+#[test]
+fn transposition_table_is_reused_between_searches() {
+    let mut tt = TranspositionTable::new(4);
+    let start = board("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3");
+
+    let first = choose_best_move_ab(&start, SearchLimits::depth(4), &mut tt);
+    assert!(tt.stats().stores > 0);
+    assert!(tt.stats().hits > 0, "{}", tt.stats());
+
+    let second = choose_best_move_ab(&start, SearchLimits::depth(4), &mut tt);
+    assert_eq!(second.best_move, first.best_move);
+    assert!(second.nodes < first.nodes, "{} vs {}", second.nodes, first.nodes);
+}
+
+#[test]
+fn multi_pv_reports_the_best_lines_with_exact_scores() {
+    let start = board("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3");
+    let result = search(&start, SearchLimits { multi_pv: 3, ..SearchLimits::depth(3) });
+    assert_eq!(result.lines.len(), 3);
+    assert_eq!(result.lines[0].pv.first().copied(), result.best_move);
+    assert_eq!(result.lines[0].score, result.score);
+    assert!(result.lines.windows(2).all(|pair| pair[0].score >= pair[1].score), "{:?}", result.lines);
+
+    let exact = search(&start, SearchLimits { exact_root_scores: true, ..SearchLimits::depth(3) });
+    let mut scores: Vec<i32> = exact.root_scores.iter().map(|&(_, score)| score).collect();
+    scores.sort_unstable_by(|a, b| b.cmp(a));
+    let reported: Vec<i32> = result.lines.iter().map(|line| line.score).collect();
+    assert_eq!(reported, scores[..3]);
+
+    let single = search(&start, SearchLimits::depth(3));
+    assert_eq!(single.lines.len(), 1);
+    assert_eq!(single.lines[0].pv, single.pv);
+}
+
+#[test]
+fn pruning_shrinks_the_tree_without_missing_tactics() {
+    let (all, none) = (SearchFeatures::ALL, SearchFeatures::NONE);
+    assert!(run_bench(4, all).nodes < run_bench(4, none).nodes);
+
+    let tactics = run_tactics(4, all);
+    assert_eq!(tactics.solved, tactics.total);
+    assert!(tactics.nodes < run_tactics(4, none).nodes);
+}
+
+#[test]
+fn null_move_is_not_tried_with_only_pawns_left() {
+    let null_move = SearchFeatures { null_move: true, ..SearchFeatures::NONE };
+    let nodes = |fen: &str, features| search(&board(fen), SearchLimits { features, ..SearchLimits::depth(6) }).nodes;
+
+    // Zugzwang is common here, so passing would prove nothing
+    let pawns = "8/8/3k4/3p4/3P4/3K4/5P2/8 w - - 0 1";
+    assert_eq!(nodes(pawns, null_move), nodes(pawns, SearchFeatures::NONE));
+    let pieces = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+    assert!(nodes(pieces, null_move) < nodes(pieces, SearchFeatures::NONE));
+}
+
+#[test]
+fn features_do_not_change_forced_mates() {
+    let start = board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    for features in [SearchFeatures::ALL, SearchFeatures::NONE] {
+        let result = search(&start, SearchLimits { features, ..SearchLimits::depth(5) });
+        assert_eq!(result.best_move, Some(ChessMove::new(Square::A1, Square::A8, None)));
+        assert_eq!(mate_in(result.score), Some(1));
+    }
+}
@@ -0,0 +1,57 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove, Color as ChessColor, MoveGen, Square};
+use chess_ai_app::engine::ordering::MoveOrderer;
+use chess_ai_app::engine::see::{hanging_pieces, see};
+
+fn board(fen: &str) -> Board {
+    Board::from_str(fen).expect("valid FEN")
+}
+
+fn mv(text: &str) -> ChessMove {
+    ChessMove::from_str(text).unwrap()
+}
+
+#[test]
+fn simple_exchanges() {
+    // Undefended pawn
+    assert_eq!(see(&board("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1"), mv("e1e5")), 100);
+    // Pawn defended by a pawn: the rook is lost for it
+    assert_eq!(see(&board("4k3/8/3p4/4p3/8/8/8/4RK2 w - - 0 1"), mv("e1e5")), -400);
+    // Pawn takes a defended knight
+    assert_eq!(see(&board("4k3/8/3p4/4n3/3P4/8/8/4K3 w - - 0 1"), mv("d4e5")), 220);
+    // En passant against nothing
+    assert_eq!(see(&board("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 2"), mv("d5e6")), 100);
+    // A quiet move to a safe square
+    assert_eq!(see(&Board::default(), mv("g1f3")), 0);
+}
+
+#[test]
+fn long_exchanges_with_x_rays_and_kings() {
+    // Knight for pawn once everything has been traded on e5
+    let tangle = board("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1");
+    assert_eq!(see(&tangle, mv("d3e5")), -220);
+    // The rook behind the first one backs it up
+    assert_eq!(see(&board("4k3/4r3/8/8/8/4p3/4R3/4R1K1 w - - 0 1"), mv("e2e3")), 100);
+    // The king recaptures an undefended attacker, but not a defended one
+    assert_eq!(see(&board("8/8/2k5/3p4/8/8/3R4/4K3 w - - 0 1"), mv("d2d5")), -400);
+    assert_eq!(see(&board("8/8/2k5/3p4/8/5B2/3R4/4K3 w - - 0 1"), mv("d2d5")), 100);
+}
+
+#[test]
+fn hanging_pieces_are_the_ones_the_opponent_wins() {
+    // The rook can take the knight and the bishop the pawn, both for free
+    let pos = board("4k3/8/8/3n4/8/7p/8/3RKB2 w - - 0 1");
+    assert_eq!(hanging_pieces(&pos, ChessColor::Black), [Square::H3, Square::D5]);
+    assert!(hanging_pieces(&pos, ChessColor::White).is_empty());
+    // Defended pieces are not hanging even when attacked
+    let defended = board("4k3/4p3/3n4/8/8/8/8/3RK3 b - - 0 1");
+    assert!(hanging_pieces(&defended, ChessColor::Black).is_empty());
+}
+
+#[test]
+fn losing_captures_are_tried_after_quiet_moves() {
+    let pos = board("4k3/8/3p4/4p3/8/8/8/4RK2 w - - 0 1");
+    let order = MoveOrderer::new().order(&pos, MoveGen::new_legal(&pos).collect(), None, 0);
+    assert_eq!(order.last(), Some(&mv("e1e5")));
+}
@@ -0,0 +1,168 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chess::{Board, BoardStatus, ChessMove, MoveGen};
+use chess_ai_app::engine::search::{MATE_BOUND, MAX_SEARCH_DEPTH, TB_WIN_SCORE};
+use chess_ai_app::engine::syzygy::{material_name, ProbeError, Tablebase, Wdl};
+use chess_ai_app::engine::{Engine, GameRecord, SearchLimits};
+
+fn board(fen: &str) -> Board {
+    Board::from_str(fen).expect("valid FEN")
+}
+
+fn mv(text: &str) -> ChessMove {
+    ChessMove::from_str(text).unwrap()
+}
+
+/// KQvK and KRvK, both win/draw/loss and distance-to-zero tables.
+fn tables() -> Tablebase {
+    Tablebase::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/tables")).unwrap()
+}
+
+#[test]
+fn material_is_named_stronger_side_first() {
+    assert_eq!(material_name(&board("7k/8/8/8/8/8/8/KQ6 w - - 0 1")), "KQvK");
+    assert_eq!(material_name(&board("7k/8/8/8/8/8/8/K7 w - - 0 1")), "KvK");
+    assert_eq!(material_name(&board("kq6/8/8/8/8/8/8/K7 w - - 0 1")), "KQvK");
+    assert_eq!(material_name(&board("k2r4/8/8/8/8/3P4/8/K2R4 b - - 0 1")), "KRPvKR");
+    assert_eq!(material_name(&board("k2n4/8/8/8/8/8/8/K2B4 w - - 0 1")), "KBvKN");
+}
+
+#[test]
+fn tables_are_found_in_a_directory() {
+    let tb = tables();
+    assert_eq!(tb.len(), 4);
+    assert_eq!(tb.max_pieces(), 3);
+
+    let empty = std::env::temp_dir().join(format!("chess_ai_syzygy_empty_{}", std::process::id()));
+    std::fs::create_dir_all(&empty).unwrap();
+    assert!(Tablebase::open(&empty).is_err());
+    assert!(Tablebase::open(empty.join("nowhere")).is_err());
+    std::fs::remove_dir_all(empty).unwrap();
+}
+
+#[test]
+fn wdl_is_probed_for_either_side() {
+    let tb = tables();
+    assert_eq!(tb.probe_wdl(&board("7k/8/8/8/8/8/8/KQ6 w - - 0 1")), Ok(Wdl::Win));
+    assert_eq!(tb.probe_wdl(&board("7k/8/8/8/8/8/8/KQ6 b - - 0 1")), Ok(Wdl::Loss));
+    // Black's queen, looked up in the same table
+    assert_eq!(tb.probe_wdl(&board("kq6/8/8/8/8/8/8/K7 w - - 0 1")), Ok(Wdl::Loss));
+    // The king takes the undefended rook
+    assert_eq!(tb.probe_wdl(&board("8/8/8/8/8/8/2kR4/7K b - - 0 1")), Ok(Wdl::Draw));
+    assert_eq!(tb.probe_wdl(&board("7k/8/8/8/8/8/8/K7 w - - 0 1")), Ok(Wdl::Draw));
+
+    assert_eq!(tb.probe_wdl(&Board::default()), Err(ProbeError::TooManyPieces));
+    assert_eq!(tb.probe_wdl(&board("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1")), Err(ProbeError::Castling));
+    assert_eq!(tb.probe_wdl(&board("7k/8/8/8/8/8/8/KB6 w - - 0 1")), Err(ProbeError::MissingTable("KBvK".to_string())));
+}
+
+#[test]
+fn dtz_counts_plies_to_mate() {
+    let tb = tables();
+    // Qf8 mates
+    assert_eq!(tb.probe_dtz(&board("7k/8/6K1/8/8/8/8/5Q2 w - - 0 1")), Ok(1));
+    assert_eq!(tb.probe_dtz(&board("5Q1k/8/6K1/8/8/8/8/8 b - - 0 1")), Ok(-1));
+    assert_eq!(tb.probe_dtz(&board("7k/8/8/8/8/8/8/K7 w - - 0 1")), Ok(0));
+
+    // The longest wins: mate in ten with the queen, in sixteen with the rook
+    assert_eq!(tb.probe_dtz(&board("8/8/8/5k2/8/8/1Q6/K7 w - - 0 1")), Ok(19));
+    assert_eq!(tb.probe_dtz(&board("8/8/8/8/8/2k5/1R6/K7 w - - 0 1")), Ok(31));
+}
+
+#[test]
+fn root_moves_keep_the_win_by_the_shortest_way() {
+    let tb = tables();
+    // The rook is attacked and undefended
+    let position = board("8/8/8/8/8/3R4/2k5/7K w - - 0 1");
+    let root = tb.root_moves(&position, 0).unwrap();
+    assert_eq!(root.wdl, Wdl::Win);
+    let moves = root.moves;
+    assert!(!moves.is_empty());
+    assert!(!moves.contains(&mv("h1g2")), "leaves the rook to be taken");
+
+    let dtz = |mv: ChessMove| -tb.probe_dtz(&position.make_move_new(mv)).unwrap();
+    let fastest = MoveGen::new_legal(&position).map(dtz).filter(|&d| d > 0).min().unwrap();
+    for &mv in &moves {
+        assert_eq!(tb.probe_wdl(&position.make_move_new(mv)), Ok(Wdl::Loss));
+        assert_eq!(dtz(mv), fastest);
+    }
+
+    // The defender holds out as long as it can
+    let defence = board("8/8/8/3k4/8/8/8/KR6 b - - 0 1");
+    let longest = MoveGen::new_legal(&defence).map(|mv| tb.probe_dtz(&defence.make_move_new(mv)).unwrap()).max().unwrap();
+    let root = tb.root_moves(&defence, 0).unwrap();
+    assert_eq!(root.wdl, Wdl::Loss);
+    for mv in root.moves {
+        assert_eq!(tb.probe_dtz(&defence.make_move_new(mv)), Ok(longest));
+    }
+
+    assert_eq!(tb.root_moves(&Board::default(), 0), Err(ProbeError::TooManyPieces));
+}
+
+#[test]
+fn root_moves_count_the_fifty_move_rule() {
+    let tb = tables();
+    // Mate is 31 plies away, but only 10 are left before the game is drawn
+    let position = board("8/8/8/8/8/2k5/1R6/K7 w - - 90 1");
+    let root = tb.root_moves(&position, 90).unwrap();
+    assert_eq!(root.wdl, Wdl::CursedWin);
+    assert_eq!(tb.root_moves(&position, 0).unwrap().wdl, Wdl::Win);
+}
+
+#[test]
+fn shallow_search_mates_with_the_rook_in_the_tablebase_distance() {
+    let mut engine = Engine::with_hash_mb(1);
+    engine.set_tablebase(Some(tables()));
+    let start = board("8/8/8/8/8/2k5/1R6/K7 w - - 0 1");
+    let plies = tables().probe_dtz(&start).unwrap();
+
+    // Both sides search a single ply, far too shallow to find the mate
+    let mut game = GameRecord::new(start);
+    while game.board().status() == BoardStatus::Ongoing {
+        assert!(game.len() < plies as usize, "no mate after {} plies", game.len());
+        engine.set_game(&game);
+        game.push(engine.search(SearchLimits::depth(1)).best_move.unwrap());
+    }
+    assert_eq!(game.board().status(), BoardStatus::Checkmate);
+    assert_eq!(game.len(), plies as usize);
+}
+
+#[test]
+fn search_reports_the_tablebase_result_at_a_covered_root() {
+    let start = board("8/8/8/5k2/8/8/1Q6/K7 w - - 0 1");
+    let mut engine = Engine::with_hash_mb(1);
+    engine.set_tablebase(Some(tables()));
+    engine.set_position(start);
+    let result = engine.search(SearchLimits::depth(3));
+    assert!(result.score > TB_WIN_SCORE - MAX_SEARCH_DEPTH && result.score < MATE_BOUND, "score {}", result.score);
+
+    let mut game = GameRecord::new(board("8/8/8/5k2/8/8/1Q6/K7 b - - 0 1"));
+    engine.set_game(&game);
+    let result = engine.search(SearchLimits::depth(3));
+    assert!(result.score < -TB_WIN_SCORE + MAX_SEARCH_DEPTH, "score {}", result.score);
+
+    // Too late to mate before the fifty-move rule
+    game = GameRecord::new(start).with_halfmove_clock(90);
+    engine.set_game(&game);
+    assert_eq!(engine.search(SearchLimits::depth(3)).score, 0);
+}
+
+#[test]
+fn search_scores_captures_into_the_tables() {
+    // Taking the knight leaves KQvK, which no depth-2 search can see through
+    let position = board("7k/8/8/3n4/8/8/8/K2Q4 w - - 0 1");
+    let mut engine = Engine::with_hash_mb(1);
+    engine.set_position(position);
+    let plain = engine.search(SearchLimits::depth(2));
+    assert!(plain.score < MATE_BOUND / 2);
+
+    engine.set_tablebase(Some(tables()));
+    let result = engine.search(SearchLimits::depth(2));
+    assert_eq!(result.best_move, Some(mv("d1d5")));
+    assert!(result.score > plain.score);
+    assert!(result.score > TB_WIN_SCORE - 10 && result.score < MATE_BOUND, "score {}", result.score);
+
+    engine.set_tablebase(None);
+    assert_eq!(engine.search(SearchLimits::depth(2)).score, plain.score);
+}
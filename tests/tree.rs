@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use chess::{Board, ChessMove, Color};
+use chess_ai_app::engine::tree::GameTree;
+use chess_ai_app::engine::GameRecord;
+
+fn mv(text: &str) -> ChessMove {
+    ChessMove::from_str(text).unwrap()
+}
+
+fn moves(record: &GameRecord) -> Vec<String> {
+    record.iter().map(|m| m.to_string()).collect()
+}
+
+fn tree(line: &[&str]) -> GameTree {
+    let mut tree = GameTree::default();
+    for text in line {
+        tree.play(mv(text));
+    }
+    tree
+}
+
+#[test]
+fn back_and_forward_redo_the_line() {
+    let mut tree = tree(&["e2e4", "e7e5", "g1f3"]);
+    assert!(tree.at_end());
+    assert_eq!(tree.back(), Some(mv("g1f3")));
+    assert_eq!(tree.back(), Some(mv("e7e5")));
+    assert_eq!(tree.ply(), 1);
+    assert!(!tree.at_end());
+    assert_eq!(moves(&tree.line()), ["e2e4", "e7e5", "g1f3"]);
+
+    assert_eq!(tree.forward(), Some(mv("e7e5")));
+    assert_eq!(tree.record().board(), tree.line().board_at(2));
+    tree.go_to(usize::MAX);
+    assert_eq!(tree.ply(), 3);
+    assert_eq!(tree.forward(), None);
+    tree.go_to(0);
+    assert_eq!(tree.back(), None);
+    assert_eq!(tree.record().board(), Board::default());
+}
+
+#[test]
+fn a_different_move_starts_a_variation() {
+    let mut tree = tree(&["e2e4", "e7e5", "g1f3"]);
+    tree.go_to(1);
+    tree.play(mv("c7c5"));
+    assert_eq!(moves(tree.record()), ["e2e4", "c7c5"]);
+    assert!(tree.at_end());
+
+    tree.back();
+    assert_eq!(tree.alternatives(), [mv("e7e5"), mv("c7c5")]);
+    // Forward follows the line last visited until another is picked
+    assert_eq!(tree.next_move(), Some(mv("c7c5")));
+    tree.cycle_alternative(1);
+    assert_eq!(tree.next_move(), Some(mv("e7e5")));
+    assert_eq!(moves(&tree.line()), ["e2e4", "e7e5", "g1f3"]);
+    tree.cycle_alternative(-1);
+    assert_eq!(moves(&tree.line()), ["e2e4", "c7c5"]);
+
+    // Replaying a known move follows it instead of adding a duplicate
+    tree.play(mv("e7e5"));
+    tree.back();
+    assert_eq!(tree.alternatives().len(), 2);
+    assert_eq!(tree.next_move(), Some(mv("e7e5")));
+}
+
+#[test]
+fn record_keeps_start_position_and_clock() {
+    let start = Board::from_str("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+    let mut record = GameRecord::new(start).with_halfmove_clock(12);
+    record.push(mv("e8d7"));
+    let mut tree = GameTree::new(record.clone());
+    assert_eq!(tree.record(), &record);
+    tree.back();
+    assert_eq!(*tree.record().start(), start);
+    assert_eq!(tree.record().halfmove_clock(), 12);
+}
+
+#[test]
+fn take_back_stops_where_the_human_is_to_move() {
+    let black = |color| color == Color::Black;
+    let mut game = tree(&["e2e4", "e7e5", "g1f3"]);
+    assert!(game.take_back(black));
+    assert_eq!(game.ply(), 1);
+
+    // Only the engine's first move is left: stepping back to the start
+    // would leave the engine to move off the end of the line
+    assert!(!game.take_back(black));
+    assert_eq!(game.ply(), 1);
+    assert_eq!(game.next_move(), Some(mv("e7e5")));
+
+    let mut opening = tree(&["e2e4"]);
+    assert!(!opening.take_back(black));
+    assert!(opening.at_end());
+    assert!(opening.take_back(|color| color == Color::White));
+    assert_eq!(opening.ply(), 0);
+}
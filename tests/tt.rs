@@ -0,0 +1,60 @@
+use chess::{ChessMove, Square};
+use chess_ai_app::engine::search::{MATE_SCORE, TB_WIN_SCORE};
+use chess_ai_app::engine::tt::{score_from_tt, score_to_tt, Bound, TranspositionTable};
+
+#[test]
+fn size_is_a_power_of_two_within_budget() {
+    let tt = TranspositionTable::new(2);
+    assert!(tt.len().is_power_of_two());
+    assert!(tt.size_mb() <= 2);
+    assert!(tt.size_mb() >= 1);
+}
+
+#[test]
+fn stores_and_probes_entries() {
+    let mut tt = TranspositionTable::new(1);
+    let mv = ChessMove::new(Square::E2, Square::E4, None);
+    tt.store(0xDEAD_BEEF, 5, 42, Bound::Lower, Some(mv));
+
+    let entry = tt.probe(0xDEAD_BEEF).expect("stored entry");
+    assert_eq!((entry.depth, entry.score, entry.bound, entry.best_move), (5, 42, Bound::Lower, Some(mv)));
+    assert_eq!(tt.probe(0xBEEF_DEAD), None);
+
+    let stats = tt.stats();
+    assert_eq!((stats.probes, stats.hits, stats.stores), (2, 1, 1));
+    assert!((stats.hit_rate() - 0.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn keeps_deeper_entry_for_same_position() {
+    let mut tt = TranspositionTable::new(1);
+    let mv = ChessMove::new(Square::G1, Square::F3, None);
+    tt.store(7, 6, 10, Bound::Exact, Some(mv));
+    tt.store(7, 2, -30, Bound::Upper, None);
+    assert_eq!(tt.probe(7).map(|e| e.depth), Some(6));
+
+    // After a new search the old entry may be overwritten, but the move survives.
+    tt.new_search();
+    tt.store(7, 2, -30, Bound::Upper, None);
+    let entry = tt.probe(7).unwrap();
+    assert_eq!((entry.depth, entry.best_move), (2, Some(mv)));
+}
+
+#[test]
+fn mate_scores_round_trip_through_ply_adjustment() {
+    for score in [MATE_SCORE - 3, -MATE_SCORE + 5, 120, -75] {
+        assert_eq!(score_from_tt(score_to_tt(score, 4), 4), score);
+    }
+    // Mate in 3 plies from a node 4 plies deep is mate in 7 from the root.
+    assert_eq!(score_from_tt(score_to_tt(MATE_SCORE - 3, 0), 4), MATE_SCORE - 7);
+}
+
+#[test]
+fn tablebase_scores_round_trip_through_ply_adjustment() {
+    for score in [TB_WIN_SCORE - 3, -TB_WIN_SCORE + 5] {
+        assert_eq!(score_from_tt(score_to_tt(score, 4), 4), score);
+    }
+    // A win stored 2 plies deep is 2 plies further off when found 6 plies deep.
+    assert_eq!(score_from_tt(score_to_tt(TB_WIN_SCORE - 2, 2), 6), TB_WIN_SCORE - 6);
+    assert_eq!(score_from_tt(score_to_tt(-TB_WIN_SCORE + 2, 2), 6), -TB_WIN_SCORE + 6);
+}
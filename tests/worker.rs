@@ -0,0 +1,55 @@
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chess::Board;
+use chess_ai_app::engine::search::SearchLimits;
+use chess_ai_app::engine::worker::{SearchOutcome, SearchWorker};
+
+fn wait_for(worker: &mut SearchWorker) -> SearchOutcome {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        if let Some(outcome) = worker.poll() {
+            return outcome;
+        }
+        assert!(Instant::now() < deadline, "search never finished");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn search_runs_in_the_background() {
+    let mut worker = SearchWorker::new(1);
+    worker.start(&Board::default(), SearchLimits::depth(3));
+    assert!(worker.is_thinking());
+
+    let outcome = wait_for(&mut worker);
+    assert_eq!(outcome.board, Board::default());
+    assert!(outcome.result.best_move.is_some());
+    assert!(!worker.is_thinking());
+}
+
+#[test]
+fn restarting_discards_the_stale_search() {
+    let mut worker = SearchWorker::new(1);
+    worker.start(&Board::default(), SearchLimits::time_ms(5_000));
+
+    let other = Board::from_str("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    worker.start(&other, SearchLimits::depth(2));
+
+    let started = Instant::now();
+    let outcome = wait_for(&mut worker);
+    assert_eq!(outcome.board, other);
+    assert!(started.elapsed() < Duration::from_secs(2), "cancelled search kept running");
+}
+
+#[test]
+fn cancel_stops_thinking() {
+    let mut worker = SearchWorker::new(1);
+    worker.start(&Board::default(), SearchLimits::time_ms(5_000));
+    worker.cancel();
+    assert!(!worker.is_thinking());
+
+    thread::sleep(Duration::from_millis(50));
+    assert!(worker.poll().is_none());
+}